@@ -19,18 +19,34 @@ use syn::{bracketed, token, Meta, Token};
 ///     },
 /// }
 /// ```
+#[derive(Clone)]
+#[non_exhaustive]
 pub enum FieldAttribute {
     Nested(NestedAttribute),
     Field(Attribute),
 }
 
 #[derive(Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub struct Attribute {
     pub pound_token: Token![#],
     pub bracket_token: token::Bracket,
     pub meta: Meta,
 }
 
+impl Attribute {
+    /// Creates an outer attribute `#[meta]` for generated code
+    pub fn new(meta: Meta) -> Self {
+        Attribute {
+            pound_token: Default::default(),
+            bracket_token: Default::default(),
+            meta,
+        }
+    }
+}
+
+#[derive(Clone)]
+#[non_exhaustive]
 pub struct NestedAttribute {
     pub pound_token: Token![#],
     pub ident_token: Token![>],
@@ -40,6 +56,7 @@ pub struct NestedAttribute {
 }
 
 #[derive(Clone)]
+#[non_exhaustive]
 pub struct CompositeAttribute {
     pub pound_token: Token![#],
     pub bracket_token: token::Bracket,
@@ -48,6 +65,7 @@ pub struct CompositeAttribute {
 }
 
 #[derive(Clone, Copy)]
+#[non_exhaustive]
 pub enum AttributeModifier {
     Star(Token![*]),
     Slash(Token![/]),
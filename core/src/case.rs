@@ -0,0 +1,77 @@
+use proc_macro2::Span;
+use syn::Ident;
+
+/// Converts a name such as `userId`, `user-id` or `HTTPStatus` to `snake_case`.
+///
+/// Every character that is not alphanumeric separates words.
+pub(crate) fn to_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake = String::with_capacity(name.len());
+
+    for (idx, &ch) in chars.iter().enumerate() {
+        if !ch.is_alphanumeric() {
+            if !snake.is_empty() && !snake.ends_with('_') {
+                snake.push('_');
+            }
+            continue;
+        }
+
+        if ch.is_uppercase() && idx > 0 {
+            let prev = chars[idx - 1];
+            let next_is_lower = chars.get(idx + 1).is_some_and(|next| next.is_lowercase());
+
+            // `userId` -> `user_id`, `HTTPStatus` -> `http_status`
+            let boundary = prev.is_lowercase()
+                || prev.is_numeric()
+                || (prev.is_uppercase() && next_is_lower);
+
+            if boundary && !snake.ends_with('_') {
+                snake.push('_');
+            }
+        }
+
+        snake.extend(ch.to_lowercase());
+    }
+
+    snake.trim_end_matches('_').to_string()
+}
+
+/// Converts a name such as `user_id` or `user-id` to `PascalCase`: `UserId`
+pub(crate) fn to_pascal_case(name: &str) -> String {
+    to_snake_case(name)
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Creates a valid field identifier for an arbitrary name, such as a JSON key.
+///
+/// Returns `None` if the name does not contain any alphanumeric characters.
+pub(crate) fn field_ident(name: &str, span: Span) -> Option<Ident> {
+    let mut snake = to_snake_case(name);
+
+    if snake.is_empty() {
+        return None;
+    }
+
+    // identifiers can't start with a number: `2fa` -> `_2fa`
+    if snake.starts_with(|ch: char| ch.is_numeric()) {
+        snake.insert(0, '_');
+    }
+
+    if syn::parse_str::<Ident>(&snake).is_ok() {
+        return Some(Ident::new(&snake, span));
+    }
+
+    // keywords: `type` -> `r#type`, except the ones that can't be raw: `self` -> `self_`
+    match snake.as_str() {
+        "self" | "super" | "crate" => Some(Ident::new(&format!("{snake}_"), span)),
+        _ => Some(Ident::new_raw(&snake, span)),
+    }
+}
@@ -0,0 +1,194 @@
+use crate::attributes::Attribute;
+use proc_macro_error::{emit_error, Diagnostic, Level};
+use syn::spanned::Spanned;
+use syn::punctuated::Punctuated;
+use syn::{Ident, Meta, Path, Token};
+
+/// Nestify's own `#[nest::...]` attributes.
+///
+/// They are resolved through [`UnpackContext`](crate::unpack_context::UnpackContext) like any
+/// other attribute, so `*`, `/` and `-` work on them, but they are stripped from the
+/// definition before it is emitted.
+#[derive(Clone, Default)]
+pub(crate) struct Directives {
+    /// `#[nest::dedup]`: merge this definition into an earlier, identical definition
+    /// of the same name instead of reporting a duplicate
+    pub dedup: bool,
+    /// `#[nest::serde]`: the definition is (de)serialized with serde, so shorthand syntax
+    /// also emits the matching `#[serde(...)]` attributes
+    pub serde: bool,
+    /// `#[nest::builder]`: generate a builder for the struct
+    pub builder: bool,
+    /// `#[nest::new]`: generate a constructor for the struct
+    pub new: bool,
+    /// `#[nest::accessors]`: generate getters and setters for the fields of the struct
+    pub accessors: bool,
+    /// `#[nest::from_variants]`: convert between the enum and the payloads defined in its variants
+    pub from_variants: bool,
+    /// `#[nest::inspect]`: generate `is_`, `as_`, `as_.._mut` and `into_` methods for the variants of the enum
+    pub inspect: bool,
+    /// `#[nest::typescript]`: generate TypeScript declarations for the definition and
+    /// every definition nested in it
+    pub typescript: bool,
+    /// `#[nest::json_schema]`: generate the JSON Schema of the definition and every definition
+    /// nested in it
+    #[cfg(feature = "json")]
+    pub json_schema: bool,
+    /// `#[nest::proto]`: generate the Protobuf declaration of the definition, with every
+    /// definition nested in it
+    pub proto: bool,
+    /// `#[nest::shape]`: generate the `nestify::Shape` of the definition and every definition
+    /// nested in it
+    pub shape: bool,
+    /// `#[nest::partial(Name)]`: define a twin of the struct named `Name`, where every field is optional
+    pub partial: Option<Ident>,
+    /// `#[nest::views(a, b)]`: define a twin of the struct for every view, fields marked with
+    /// `#[nest::only(a)]` only belong to the view `a`
+    pub views: Vec<Ident>,
+}
+
+/// Every attribute in the `nest::` namespace, on definitions, fields and variants, used for diagnostics
+const SUPPORTED: &[&str] = &["dedup", "serde", "builder", "new", "accessors", "from_variants", "inspect", "typescript", "json_schema", "proto", "shape", "partial", "views", "versions", "dump", "only", "tag", "since", "until"];
+
+/// Returns `true` if the path is in the `nest::` namespace
+pub(crate) fn is_directive(path: &Path) -> bool {
+    path.leading_colon.is_none()
+        && path.segments.len() == 2
+        && path.segments[0].ident == "nest"
+}
+
+impl Directives {
+    /// Splits the effective attributes of a definition into the attributes that should be
+    /// emitted and the directives that apply to it.
+    pub fn extract(attributes: Vec<Attribute>) -> (Vec<Attribute>, Directives) {
+        let mut directives = Directives::default();
+
+        let attributes = attributes
+            .into_iter()
+            .filter(|attr| {
+                if !is_directive(attr.meta.path()) {
+                    return true;
+                }
+
+                directives.apply(&attr.meta);
+                false
+            })
+            .collect();
+
+        (attributes, directives)
+    }
+
+    fn apply(&mut self, meta: &Meta) {
+        let name = &meta.path().segments[1].ident;
+
+        match (name.to_string().as_str(), meta) {
+            ("dedup", Meta::Path(_)) => self.dedup = true,
+            ("serde", Meta::Path(_)) => self.serde = true,
+            ("builder", Meta::Path(_)) => self.builder = true,
+            ("new", Meta::Path(_)) => self.new = true,
+            ("accessors", Meta::Path(_)) => self.accessors = true,
+            ("from_variants", Meta::Path(_)) => self.from_variants = true,
+            ("inspect", Meta::Path(_)) => self.inspect = true,
+            ("typescript", Meta::Path(_)) => self.typescript = true,
+            #[cfg(feature = "json")]
+            ("json_schema", Meta::Path(_)) => self.json_schema = true,
+            #[cfg(not(feature = "json"))]
+            ("json_schema", Meta::Path(_)) => emit_error!(
+                meta.span(),
+                "`#[nest::json_schema]` requires the `json` feature of nestify"
+            ),
+            ("proto", Meta::Path(_)) => self.proto = true,
+            ("shape", Meta::Path(_)) => self.shape = true,
+            ("dedup" | "serde" | "builder" | "new" | "accessors" | "from_variants" | "inspect" | "typescript" | "json_schema" | "proto" | "shape", _) => emit_error!(
+                meta.span(),
+                "`#[nest::{}]` does not take any arguments", name
+            ),
+            ("partial", Meta::List(list)) => match list.parse_args::<Ident>() {
+                Ok(partial) => self.partial = Some(partial),
+                Err(err) => emit_error!(
+                    err.span(),
+                    "expected the name of the partial struct";
+                    help = "for example `#[nest::partial(UserPatch)]`"
+                ),
+            },
+            ("partial", _) => emit_error!(
+                meta.span(),
+                "`#[nest::partial]` expects the name of the partial struct";
+                help = "for example `#[nest::partial(UserPatch)]`"
+            ),
+            ("views", Meta::List(list)) => {
+                match list.parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated) {
+                    Ok(views) if !views.is_empty() => self.views = views.into_iter().collect(),
+                    _ => emit_error!(
+                        list.span(),
+                        "expected the names of the views";
+                        help = "for example `#[nest::views(request, response)]`"
+                    ),
+                }
+            }
+            ("views", _) => emit_error!(
+                meta.span(),
+                "`#[nest::views]` expects the names of the views";
+                help = "for example `#[nest::views(request, response)]`"
+            ),
+            // the outermost definition is versioned before anything is unpacked
+            ("versions", _) => emit_error!(
+                meta.span(),
+                "`#[nest::versions]` is only supported on the outermost definition"
+            ),
+            #[cfg(not(feature = "dump"))]
+            ("dump", _) => emit_error!(
+                meta.span(),
+                "`#[nest::dump]` requires the `dump` feature of nestify"
+            ),
+            // the expansion of the whole invocation is dumped
+            #[cfg(feature = "dump")]
+            ("dump", _) => emit_error!(
+                meta.span(),
+                "`#[nest::dump]` is only supported on the outermost definition"
+            ),
+            _ => unsupported(name, "definitions").emit(),
+        }
+    }
+}
+
+/// Reports and removes the directives left on a field or variant, once the ones that apply to
+/// it have been taken out. `position` names what the attributes are written on.
+pub(crate) fn reject(attributes: Vec<Attribute>, position: &str) -> Vec<Attribute> {
+    rejected(&attributes, position).into_iter().for_each(Diagnostic::emit);
+
+    attributes
+        .into_iter()
+        .filter(|attr| !is_directive(attr.meta.path()))
+        .collect()
+}
+
+/// The errors for the directives in `attributes`, which aren't supported on `position`
+pub(crate) fn rejected(attributes: &[Attribute], position: &str) -> Vec<Diagnostic> {
+    attributes
+        .iter()
+        .map(|attr| attr.meta.path())
+        .filter(|path| is_directive(path))
+        .map(|path| unsupported(&path.segments[1].ident, position))
+        .collect()
+}
+
+fn unsupported(name: &Ident, position: &str) -> Diagnostic {
+    match SUPPORTED.contains(&name.to_string().as_str()) {
+        true => Diagnostic::spanned(
+            name.span(),
+            Level::Error,
+            format!("`#[nest::{}]` is not supported on {}", name, position),
+        ),
+        false => Diagnostic::spanned(name.span(), Level::Error, format!("unknown nestify attribute `nest::{}`", name))
+            .help(format!("supported attributes are: {}", supported_list())),
+    }
+}
+
+fn supported_list() -> String {
+    SUPPORTED
+        .iter()
+        .map(|name| format!("`nest::{name}`"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,213 @@
+use crate::attributes::Attribute;
+use crate::special_data::{SpecialFields, SpecialVariant};
+use proc_macro2::TokenStream;
+use proc_macro_error::{Diagnostic, Level};
+use quote::ToTokens;
+use syn::parse::{Parse, ParseStream};
+use syn::ext::IdentExt;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{Expr, ExprGroup, ExprLit, ExprParen, ExprUnary, Ident, Lit, LitStr, Meta, Token, UnOp};
+
+/// The value assigned to an enum variant
+#[derive(Clone)]
+#[non_exhaustive]
+pub enum Discriminant {
+    /// `Variant = 1`, emitted as written
+    Expr {
+        eq_token: Token![=],
+        expr: Box<Expr>,
+    },
+    /// `Variant = "variant"`, the string form of a unit variant.
+    /// It is not emitted, but generated into `Display`, `FromStr` and `AsRef<str>`.
+    Str {
+        eq_token: Token![=],
+        value: LitStr,
+    },
+}
+
+impl Parse for Discriminant {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let eq_token = input.parse()?;
+
+        if input.peek(LitStr) {
+            Ok(Discriminant::Str {
+                eq_token,
+                value: input.parse()?,
+            })
+        } else {
+            Ok(Discriminant::Expr {
+                eq_token,
+                expr: input.parse()?,
+            })
+        }
+    }
+}
+
+impl ToTokens for Discriminant {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Discriminant::Expr { eq_token, expr } => {
+                eq_token.to_tokens(tokens);
+                expr.to_tokens(tokens);
+            }
+            Discriminant::Str { eq_token, value } => {
+                eq_token.to_tokens(tokens);
+                value.to_tokens(tokens);
+            }
+        }
+    }
+}
+
+/// Integer types an enum can be represented as, with the range of values they hold.
+/// The ranges of `usize` and `isize` depend on the target, so they are not checked.
+const INTEGER_REPRS: &[(&str, Option<(i128, i128)>)] = &[
+    ("u8", Some((u8::MIN as i128, u8::MAX as i128))),
+    ("u16", Some((u16::MIN as i128, u16::MAX as i128))),
+    ("u32", Some((u32::MIN as i128, u32::MAX as i128))),
+    ("u64", Some((u64::MIN as i128, u64::MAX as i128))),
+    ("u128", Some((0, i128::MAX))),
+    ("i8", Some((i8::MIN as i128, i8::MAX as i128))),
+    ("i16", Some((i16::MIN as i128, i16::MAX as i128))),
+    ("i32", Some((i32::MIN as i128, i32::MAX as i128))),
+    ("i64", Some((i64::MIN as i128, i64::MAX as i128))),
+    ("i128", Some((i128::MIN, i128::MAX))),
+    ("usize", None),
+    ("isize", None),
+];
+
+/// Checks the integer discriminants of an enum against each other and against the
+/// integer `#[repr]` in its effective attributes.
+///
+/// Only literal values are known at expansion time. The variants after a discriminant
+/// that is not a literal are skipped until the next literal one.
+///
+/// The errors are returned to be emitted by the caller.
+pub(crate) fn validate(attrs: &[Attribute], variants: &Punctuated<SpecialVariant, Token![,]>) -> Vec<Diagnostic> {
+    let mut errors = vec![];
+
+    // the other rules of string values are checked when they are taken out of the variants
+    if variants
+        .iter()
+        .any(|variant| matches!(variant.discriminant, Some(Discriminant::Str { .. })))
+    {
+        let mut assigned: Vec<(String, &Ident)> = vec![];
+        for variant in variants {
+            // variants without a value use their name
+            let (value, span) = match &variant.discriminant {
+                Some(Discriminant::Str { value, .. }) => (value.value(), value.span()),
+                Some(Discriminant::Expr { .. }) => continue,
+                None => (variant.ident.unraw().to_string(), variant.ident.span()),
+            };
+
+            match assigned.iter().find(|(assigned, _)| *assigned == value) {
+                Some((_, first)) => errors.push(Diagnostic::spanned(
+                    span,
+                    Level::Error,
+                    format!("string value {:?} of `{}` is already assigned to `{}`", value, variant.ident, first),
+                )),
+                None => assigned.push((value, &variant.ident)),
+            }
+        }
+        return errors;
+    }
+
+    let repr = repr_type(attrs);
+
+    // explicit discriminants on enums with fields need an integer representation
+    let has_fields = variants
+        .iter()
+        .any(|variant| !matches!(variant.fields, SpecialFields::Unit));
+    if has_fields && repr.is_none() {
+        for discriminant in variants.iter().filter_map(|variant| variant.discriminant.as_ref()) {
+            errors.push(
+                Diagnostic::spanned(
+                    discriminant.span(),
+                    Level::Error,
+                    "explicit discriminants on enums with fields require an integer `#[repr]`".to_string(),
+                )
+                .help("add `#[repr(u8)]`, or another integer type, to the enum".to_string()),
+            );
+        }
+    }
+
+    let range = repr
+        .as_ref()
+        .and_then(|repr| INTEGER_REPRS.iter().find(|(name, _)| repr == name))
+        .and_then(|(_, range)| *range);
+
+    let mut assigned: Vec<(i128, &Ident)> = vec![];
+    let mut next = Some(0);
+
+    for variant in variants {
+        let (value, span) = match &variant.discriminant {
+            Some(discriminant @ Discriminant::Expr { expr, .. }) => (literal_value(expr), discriminant.span()),
+            Some(Discriminant::Str { .. }) => continue,
+            // implicit discriminants count up from the previous variant
+            None => (next, variant.ident.span()),
+        };
+        next = value.and_then(|value| value.checked_add(1));
+
+        let Some(value) = value else {
+            continue;
+        };
+
+        if let (Some(repr), Some((min, max))) = (&repr, range) {
+            if value < min || value > max {
+                errors.push(
+                    Diagnostic::spanned(
+                        span,
+                        Level::Error,
+                        format!(
+                            "discriminant value `{}` of `{}` is out of range for `#[repr({})]`",
+                            value, variant.ident, repr
+                        ),
+                    )
+                    .note(format!("`{}` holds values from {} to {}", repr, min, max)),
+                );
+            }
+        }
+
+        match assigned.iter().find(|(assigned, _)| *assigned == value) {
+            Some((_, first)) => errors.push(Diagnostic::spanned(
+                span,
+                Level::Error,
+                format!("discriminant value `{}` of `{}` is already assigned to `{}`", value, variant.ident, first),
+            )),
+            None => assigned.push((value, &variant.ident)),
+        }
+    }
+
+    errors
+}
+
+/// The value of a discriminant written as an integer literal, such as `1`, `-1` or `0xff`
+pub(crate) fn literal_value(expr: &Expr) -> Option<i128> {
+    match expr {
+        Expr::Lit(ExprLit { lit: Lit::Int(int), .. }) => int.base10_parse().ok(),
+        Expr::Lit(ExprLit { lit: Lit::Byte(byte), .. }) => Some(byte.value() as i128),
+        Expr::Unary(ExprUnary { op: UnOp::Neg(_), expr, .. }) => literal_value(expr)?.checked_neg(),
+        Expr::Paren(ExprParen { expr, .. }) | Expr::Group(ExprGroup { expr, .. }) => literal_value(expr),
+        _ => None,
+    }
+}
+
+/// The integer type in `#[repr(...)]`, if there is one
+pub(crate) fn repr_type(attrs: &[Attribute]) -> Option<Ident> {
+    attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            Meta::List(list) if list.path.is_ident("repr") => list
+                .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                .ok(),
+            _ => None,
+        })
+        .flatten()
+        .find_map(|meta| match meta {
+            Meta::Path(path) => path
+                .get_ident()
+                .filter(|ident| INTEGER_REPRS.iter().any(|(name, _)| *ident == name))
+                .cloned(),
+            _ => None,
+        })
+}
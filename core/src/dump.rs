@@ -0,0 +1,99 @@
+//! `#[nest::dump]` shows what an invocation expands to, with the effective attributes after
+//! `*`, `/` and `-` are applied, without `cargo expand`.
+//!
+//! The expansion is emitted as the associated constant `Name::NESTIFY_EXPANSION`, so every
+//! invocation in a module can be dumped, and written to `$NESTIFY_DUMP_DIR` or, in crates with a
+//! build script, to `$OUT_DIR`. Versioned types get it in every version. The file is named after
+//! the crate, the source file and the type, `{crate}/src/models/{Name}.rs`, so types of the same
+//! name in other files and crates don't overwrite it.
+
+use crate::directives::is_directive;
+use crate::special_data::Special;
+use proc_macro2::TokenStream;
+use proc_macro_error::emit_error;
+use quote::quote;
+use std::path::{Component, PathBuf};
+use syn::{Generics, Ident, Meta, Visibility};
+
+/// Removes `#[nest::dump]` from the outermost definition, returns `true` if it was there
+pub(crate) fn take(root: &mut Special) -> bool {
+    let Some(index) = root.attrs.iter().position(|attr| is_dump(&attr.meta)) else {
+        return false;
+    };
+    let attr = root.attrs.remove(index);
+
+    if !matches!(attr.meta, Meta::Path(_)) {
+        emit_error!(attr.meta, "`#[nest::dump]` does not take any arguments");
+    }
+    true
+}
+
+fn is_dump(meta: &Meta) -> bool {
+    let path = meta.path();
+    is_directive(path) && path.segments[1].ident == "dump"
+}
+
+/// `root::NESTIFY_EXPANSION` with the pretty printed `expansion` of the definition `root`, or
+/// `version::root::NESTIFY_EXPANSION` for each of its `versions`
+pub(crate) fn dump(
+    vis: &Visibility,
+    root: &Ident,
+    generics: &Generics,
+    versions: &[Ident],
+    expansion: &TokenStream,
+) -> TokenStream {
+    let pretty = match syn::parse2::<syn::File>(expansion.clone()) {
+        Ok(file) => prettyplease::unparse(&file),
+        // the compiler reports what is wrong with the expansion
+        Err(_) => expansion.to_string(),
+    };
+
+    if let Some(dir) = dir() {
+        let path = dir.join(file(root));
+        let written = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(&path, &pretty));
+        if let Err(err) = written {
+            emit_error!(
+                root, "cannot write the expansion to `{}`: {}", path.display(), err;
+                note = "the directory is set with `NESTIFY_DUMP_DIR`, or `OUT_DIR` in crates with a build script"
+            );
+        }
+    }
+
+    let types = match versions {
+        [] => vec![quote!(#root)],
+        versions => versions.iter().map(|version| quote!(#version::#root)).collect(),
+    };
+    let doc = format!(" The expansion of `{root}`, from `#[nest::dump]`");
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote!(#(
+        impl #impl_generics #types #ty_generics #where_clause {
+            #[doc = #doc]
+            #[allow(dead_code)]
+            #vis const NESTIFY_EXPANSION: &'static str = #pretty;
+        }
+    )*)
+}
+
+/// The path of the dump of `root` in the directory: `{crate}/{source file}/{Name}.rs`
+fn file(root: &Ident) -> PathBuf {
+    let mut path = PathBuf::from(std::env::var("CARGO_CRATE_NAME").unwrap_or_default());
+
+    // only the names in the path, which can be absolute or point out of the crate
+    let source = PathBuf::from(root.span().file()).with_extension("");
+    path.extend(source.components().filter_map(|component| match component {
+        Component::Normal(name) => Some(name),
+        _ => None,
+    }));
+
+    path.join(format!("{root}.rs"))
+}
+
+/// The directory the expansion is written to
+fn dir() -> Option<PathBuf> {
+    std::env::var_os("NESTIFY_DUMP_DIR")
+        .or_else(|| std::env::var_os("OUT_DIR"))
+        .map(PathBuf::from)
+}
@@ -5,6 +5,7 @@ use syn::{Token, AngleBracketedGenericArguments};
 use syn::spanned::Spanned;
 
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub struct FishHook {
     pub prefix: Token![||],
     pub generics: AngleBracketedGenericArguments,
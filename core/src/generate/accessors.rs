@@ -0,0 +1,66 @@
+use crate::generate::{Definition, UnpackedBody};
+use crate::unpack::UnpackedFields;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+
+/// Generates accessors for the named fields of structs with `#[nest::accessors]`.
+///
+/// Every field gets a getter, `_mut` getter and `set_` setter. Fields of type
+/// `Option<_>` holding a definition written inline also get `_or_insert_with`.
+pub(super) fn generate(definition: &Definition) -> TokenStream {
+    if !definition.directives.accessors {
+        return TokenStream::new();
+    }
+
+    let UnpackedBody::Struct(UnpackedFields::Named(fields)) = definition.body else {
+        return TokenStream::new();
+    };
+
+    let accessors = fields.iter().filter_map(|field| {
+        let name = field.ident.as_ref()?;
+        let ty = &field.ty;
+        let name_mut = format_ident!("{}_mut", name.unraw());
+        let set_name = format_ident!("set_{}", name.unraw());
+
+        let or_insert_with = field
+            .inline
+            .as_ref()
+            .filter(|_| field.optional)
+            .map(|inline| {
+                let child = &inline.ty;
+                let or_insert_with = format_ident!("{}_or_insert_with", name.unraw());
+                quote!(
+                    pub fn #or_insert_with(&mut self, f: impl ::core::ops::FnOnce() -> #child) -> &mut #child {
+                        self.#name.get_or_insert_with(f)
+                    }
+                )
+            });
+
+        Some(quote!(
+            pub fn #name(&self) -> &#ty {
+                &self.#name
+            }
+
+            pub fn #name_mut(&mut self) -> &mut #ty {
+                &mut self.#name
+            }
+
+            pub fn #set_name(&mut self, value: #ty) -> &mut Self {
+                self.#name = value;
+                self
+            }
+
+            #or_insert_with
+        ))
+    });
+
+    let ident = definition.ident;
+    let (impl_generics, ty_generics, where_clause) = definition.generics.split_for_impl();
+
+    quote!(
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #(#accessors)*
+        }
+    )
+}
@@ -0,0 +1,139 @@
+use crate::generate::{Definition, UnpackedBody};
+use crate::registry::DefinitionKind;
+use crate::unpack::{option_inner, UnpackedField, UnpackedFields};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{LitStr, Type};
+
+/// Generates a builder for named structs with `#[nest::builder]`.
+///
+/// A field whose type is a struct defined inline, that has a builder as well, is set
+/// through a closure over the builder of the child: `.address(|a| a.city("Berlin"))`.
+pub(super) fn generate(definition: &Definition) -> TokenStream {
+    if !definition.directives.builder {
+        return TokenStream::new();
+    }
+
+    let UnpackedBody::Struct(UnpackedFields::Named(fields)) = definition.body else {
+        return TokenStream::new();
+    };
+
+    let ident = definition.ident;
+    let vis = definition.vis;
+    let generics = definition.generics;
+    let where_clause = &generics.where_clause;
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let builder = format_ident!("{}Builder", ident);
+
+    let mut storage = vec![];
+    let mut setters = vec![];
+    let mut values = vec![];
+
+    for field in fields {
+        let Some(name) = &field.ident else {
+            continue;
+        };
+        let missing = LitStr::new(
+            &format!("missing field `{}` in `{}`", name.unraw(), ident),
+            name.span(),
+        );
+        let fallback = match (&field.default, field.optional) {
+            (Some(value), _) => quote!(#value),
+            (None, true) => quote!(::core::option::Option::None),
+            (None, false) => quote!(return ::core::result::Result::Err(#missing)),
+        };
+
+        if let Some(child) = child_builder(field) {
+            storage.push(quote!(#name: ::core::option::Option<#child>));
+            setters.push(quote!(
+                pub fn #name(mut self, build: impl ::core::ops::FnOnce(#child) -> #child) -> Self {
+                    self.#name = ::core::option::Option::Some(build(self.#name.take().unwrap_or_default()));
+                    self
+                }
+            ));
+
+            let built = if field.optional {
+                quote!(::core::option::Option::Some(builder.build()?))
+            } else {
+                quote!(builder.build()?)
+            };
+            values.push(quote!(
+                #name: match self.#name {
+                    ::core::option::Option::Some(builder) => #built,
+                    ::core::option::Option::None => #fallback,
+                }
+            ));
+        } else {
+            // optional fields are set with the inner value
+            let (ty, value) = match option_inner(&field.ty) {
+                Some(inner) => (inner, quote!(::core::option::Option::Some(value))),
+                None => (&field.ty, quote!(value)),
+            };
+
+            storage.push(quote!(#name: ::core::option::Option<#ty>));
+            setters.push(quote!(
+                pub fn #name(mut self, value: #ty) -> Self {
+                    self.#name = ::core::option::Option::Some(value);
+                    self
+                }
+            ));
+            values.push(quote!(
+                #name: match self.#name {
+                    ::core::option::Option::Some(value) => #value,
+                    ::core::option::Option::None => #fallback,
+                }
+            ));
+        }
+    }
+
+    let names = fields.iter().map(|field| &field.ident);
+
+    quote!(
+        #vis struct #builder #generics #where_clause {
+            #(#storage),*
+        }
+
+        impl #impl_generics ::core::default::Default for #builder #ty_generics #where_clause {
+            fn default() -> Self {
+                Self {
+                    #(#names: ::core::option::Option::None),*
+                }
+            }
+        }
+
+        impl #impl_generics #builder #ty_generics #where_clause {
+            #(#setters)*
+
+            pub fn build(self) -> ::core::result::Result<#ident #ty_generics, &'static str> {
+                ::core::result::Result::Ok(#ident {
+                    #(#values),*
+                })
+            }
+        }
+
+        impl #impl_generics #ident #ty_generics #where_clause {
+            pub fn builder() -> #builder #ty_generics {
+                ::core::default::Default::default()
+            }
+        }
+    )
+}
+
+/// The builder type of the field's inline definition, if it has one
+fn child_builder(field: &UnpackedField) -> Option<Type> {
+    let inline = field.inline.as_ref()?;
+
+    if inline.kind != DefinitionKind::NamedStruct || !inline.directives.builder {
+        return None;
+    }
+
+    let mut ty = inline.ty.clone();
+    let Type::Path(path) = &mut ty else {
+        return None;
+    };
+    let segment = path.path.segments.last_mut()?;
+    segment.ident = format_ident!("{}Builder", segment.ident);
+
+    Some(ty)
+}
@@ -0,0 +1,84 @@
+use crate::generate::{Definition, UnpackedBody};
+use crate::unpack::{UnpackedField, UnpackedFields};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::Type;
+
+/// Generates `fn new(..)` for structs with `#[nest::new]`.
+///
+/// Fields with a declared default are filled in, every other field is a parameter.
+/// `String` parameters accept `impl Into<String>`.
+pub(super) fn generate(definition: &Definition) -> TokenStream {
+    if !definition.directives.new {
+        return TokenStream::new();
+    }
+
+    let UnpackedBody::Struct(fields) = definition.body else {
+        return TokenStream::new();
+    };
+
+    let mut params = vec![];
+    let mut values = vec![];
+
+    let body = match fields {
+        UnpackedFields::Named(fields) => {
+            for field in fields {
+                let Some(name) = &field.ident else {
+                    continue;
+                };
+                let value = parameter(field, name.clone(), &mut params);
+                values.push(quote!(#name: #value));
+            }
+            quote!({ #(#values),* })
+        }
+        UnpackedFields::Unnamed(fields) => {
+            for (idx, field) in fields.iter().enumerate() {
+                values.push(parameter(field, format_ident!("_{}", idx), &mut params));
+            }
+            quote!(( #(#values),* ))
+        }
+        UnpackedFields::Unit => TokenStream::new(),
+    };
+
+    let ident = definition.ident;
+    let (impl_generics, ty_generics, where_clause) = definition.generics.split_for_impl();
+
+    quote!(
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #[allow(clippy::too_many_arguments, clippy::new_without_default)]
+            pub fn new(#(#params),*) -> Self {
+                Self #body
+            }
+        }
+    )
+}
+
+/// Returns the value of the field in the constructor, adding a parameter if it is required
+fn parameter(field: &UnpackedField, name: syn::Ident, params: &mut Vec<TokenStream>) -> TokenStream {
+    if let Some(value) = &field.default {
+        return quote!(#value);
+    }
+
+    let ty = &field.ty;
+    if is_string(ty) {
+        params.push(quote!(#name: impl ::core::convert::Into<#ty>));
+        quote!(#name.into())
+    } else {
+        params.push(quote!(#name: #ty));
+        quote!(#name)
+    }
+}
+
+/// Returns `true` if the type is `String`
+fn is_string(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+
+    path.qself.is_none()
+        && path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "String" && segment.arguments.is_none())
+}
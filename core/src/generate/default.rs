@@ -0,0 +1,71 @@
+use crate::generate::{Definition, UnpackedBody};
+use crate::unpack::UnpackedFields;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+
+/// Generates `impl Default` for structs with declared field defaults.
+///
+/// In serde mode, every declared default of a named field also gets a helper function
+/// that is referenced by the field's `#[serde(default = "...")]`.
+pub(super) fn generate(definition: &Definition) -> TokenStream {
+    let UnpackedBody::Struct(UnpackedFields::Named(fields) | UnpackedFields::Unnamed(fields)) = definition.body else {
+        return TokenStream::new();
+    };
+
+    if fields.iter().all(|field| field.default.is_none()) {
+        return TokenStream::new();
+    }
+
+    let ident = definition.ident;
+    let (impl_generics, ty_generics, where_clause) = definition.generics.split_for_impl();
+
+    let values = fields.iter().map(|field| {
+        let value = match &field.default {
+            Some(value) => quote!(#value),
+            None => quote!(::core::default::Default::default()),
+        };
+        match &field.ident {
+            Some(name) => quote!(#name: #value),
+            None => value,
+        }
+    });
+
+    let body = match definition.body {
+        UnpackedBody::Struct(UnpackedFields::Named(_)) => quote!({ #(#values),* }),
+        _ => quote!(( #(#values),* )),
+    };
+
+    let helpers = if definition.directives.serde {
+        let helpers = fields.iter().filter_map(|field| {
+            let value = field.default.as_ref()?;
+            let ty = &field.ty;
+            let helper = format_ident!("default_{}", field.ident.as_ref()?.unraw());
+
+            Some(quote!(
+                #[allow(dead_code)]
+                fn #helper() -> #ty {
+                    #value
+                }
+            ))
+        });
+
+        quote!(
+            impl #impl_generics #ident #ty_generics #where_clause {
+                #(#helpers)*
+            }
+        )
+    } else {
+        TokenStream::new()
+    };
+
+    quote!(
+        impl #impl_generics ::core::default::Default for #ident #ty_generics #where_clause {
+            fn default() -> Self {
+                Self #body
+            }
+        }
+
+        #helpers
+    )
+}
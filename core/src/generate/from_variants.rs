@@ -0,0 +1,68 @@
+use crate::generate::{Definition, UnpackedBody};
+use crate::unpack::{UnpackedField, UnpackedFields};
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Generates conversions for enums with `#[nest::from_variants]`.
+///
+/// Every variant with a single field, whose type was defined inline in the variant,
+/// gets `From<Payload> for Enum` and `TryFrom<Enum> for Payload`.
+pub(super) fn generate(definition: &Definition) -> TokenStream {
+    if !definition.directives.from_variants {
+        return TokenStream::new();
+    }
+
+    let UnpackedBody::Enum(variants) = definition.body else {
+        return TokenStream::new();
+    };
+
+    let ident = definition.ident;
+    let (impl_generics, ty_generics, where_clause) = definition.generics.split_for_impl();
+
+    let conversions = variants.iter().filter_map(|variant| {
+        let (construct, pattern, payload) = match &variant.fields {
+            UnpackedFields::Unnamed(fields) => {
+                let payload = single_inline(fields)?;
+                (quote!((value)), quote!((value)), payload)
+            }
+            UnpackedFields::Named(fields) => {
+                let payload = single_inline(fields)?;
+                let name = &payload.ident;
+                (quote!({ #name: value }), quote!({ #name: value }), payload)
+            }
+            UnpackedFields::Unit => return None,
+        };
+        let variant = &variant.ident;
+        let payload = &payload.ty;
+
+        Some(quote!(
+            impl #impl_generics ::core::convert::From<#payload> for #ident #ty_generics #where_clause {
+                fn from(value: #payload) -> Self {
+                    Self::#variant #construct
+                }
+            }
+
+            impl #impl_generics ::core::convert::TryFrom<#ident #ty_generics> for #payload #where_clause {
+                type Error = #ident #ty_generics;
+
+                fn try_from(value: #ident #ty_generics) -> ::core::result::Result<Self, Self::Error> {
+                    #[allow(unreachable_patterns)]
+                    match value {
+                        #ident::#variant #pattern => ::core::result::Result::Ok(value),
+                        other => ::core::result::Result::Err(other),
+                    }
+                }
+            }
+        ))
+    });
+
+    quote!(#(#conversions)*)
+}
+
+/// Returns the only field if its type is a definition written inline
+fn single_inline(fields: &[UnpackedField]) -> Option<&UnpackedField> {
+    match fields {
+        [field] if field.inline.as_ref().is_some_and(|inline| inline.ty == field.ty) => Some(field),
+        _ => None,
+    }
+}
@@ -0,0 +1,117 @@
+use crate::case::to_snake_case;
+use crate::generate::{Definition, UnpackedBody};
+use crate::unpack::{UnpackedField, UnpackedFields, UnpackedVariant};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+
+/// Generates inspection methods for the variants of enums with `#[nest::inspect]`.
+///
+/// Every variant gets `is_`. Variants with fields also get `as_`, `as_.._mut` and `into_`,
+/// which return the field, or a tuple of all fields if there are several.
+pub(super) fn generate(definition: &Definition) -> TokenStream {
+    if !definition.directives.inspect {
+        return TokenStream::new();
+    }
+
+    let UnpackedBody::Enum(variants) = definition.body else {
+        return TokenStream::new();
+    };
+
+    let methods = variants.iter().map(variant_methods);
+
+    let ident = definition.ident;
+    let (impl_generics, ty_generics, where_clause) = definition.generics.split_for_impl();
+
+    quote!(
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #(#methods)*
+        }
+    )
+}
+
+fn variant_methods(variant: &UnpackedVariant) -> TokenStream {
+    let ident = &variant.ident;
+    let snake = to_snake_case(&ident.unraw().to_string());
+    let is_variant = format_ident!("is_{}", snake);
+
+    let is = quote!(
+        pub fn #is_variant(&self) -> bool {
+            ::core::matches!(self, Self::#ident { .. })
+        }
+    );
+
+    let (pattern, bindings, types) = match &variant.fields {
+        UnpackedFields::Named(fields) => {
+            let bindings = bindings(fields);
+            (quote!({ #(#bindings),* }), bindings, types(fields))
+        }
+        UnpackedFields::Unnamed(fields) => {
+            let bindings = bindings(fields);
+            (quote!(( #(#bindings),* )), bindings, types(fields))
+        }
+        UnpackedFields::Unit => return is,
+    };
+
+    let as_variant = format_ident!("as_{}", snake);
+    let as_variant_mut = format_ident!("as_{}_mut", snake);
+    let into_variant = format_ident!("into_{}", snake);
+
+    // a single field is returned as is, several as a tuple
+    let (value, ty, ty_ref, ty_mut) = match (bindings.as_slice(), types.as_slice()) {
+        ([binding], [ty]) => (quote!(#binding), quote!(#ty), quote!(&#ty), quote!(&mut #ty)),
+        _ => (
+            quote!(( #(#bindings),* )),
+            quote!(( #(#types),* )),
+            quote!(( #(&#types),* )),
+            quote!(( #(&mut #types),* )),
+        ),
+    };
+
+    quote!(
+        #is
+
+        pub fn #as_variant(&self) -> ::core::option::Option<#ty_ref> {
+            #[allow(unreachable_patterns)]
+            match self {
+                Self::#ident #pattern => ::core::option::Option::Some(#value),
+                _ => ::core::option::Option::None,
+            }
+        }
+
+        pub fn #as_variant_mut(&mut self) -> ::core::option::Option<#ty_mut> {
+            #[allow(unreachable_patterns)]
+            match self {
+                Self::#ident #pattern => ::core::option::Option::Some(#value),
+                _ => ::core::option::Option::None,
+            }
+        }
+
+        pub fn #into_variant(self) -> ::core::result::Result<#ty, Self> {
+            #[allow(unreachable_patterns)]
+            match self {
+                Self::#ident #pattern => ::core::result::Result::Ok(#value),
+                other => ::core::result::Result::Err(other),
+            }
+        }
+    )
+}
+
+/// Names to bind the fields of a variant to in a pattern
+fn bindings(fields: &[UnpackedField]) -> Vec<TokenStream> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(idx, field)| match &field.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let ident = format_ident!("_{}", idx);
+                quote!(#ident)
+            }
+        })
+        .collect()
+}
+
+fn types(fields: &[UnpackedField]) -> Vec<&syn::Type> {
+    fields.iter().map(|field| &field.ty).collect()
+}
@@ -0,0 +1,260 @@
+use crate::attributes::Attribute;
+use crate::generate::{classify, docs, Definition, EnumRepr, Primitive, TypeKind, UnpackedBody};
+use crate::serde_attrs::SerdeAttrs;
+use crate::unpack::{UnpackedField, UnpackedFields, UnpackedVariant};
+use proc_macro2::TokenStream;
+use quote::quote;
+use serde_json::{json, Map, Value};
+use std::cell::RefCell;
+use syn::ext::IdentExt;
+use syn::Type;
+
+/// Generates `fn json_schema()` for the definition `#[nest::json_schema]` was written on, with
+/// the schemas of the definitions nested in it under `$defs`.
+///
+/// `schemas` collects the schemas of the nested definitions, which are unpacked before the
+/// definition itself.
+pub(crate) fn generate(definition: &Definition, schemas: &RefCell<Map<String, Value>>, root: bool) -> TokenStream {
+    let name = definition.ident.unraw().to_string();
+    let schema = schema(definition);
+
+    if !root {
+        // deduplicated definitions have the same schema
+        schemas.borrow_mut().entry(name).or_insert(schema);
+        return TokenStream::new();
+    }
+
+    let defs = schemas.borrow().clone();
+
+    let mut document = Map::new();
+    document.insert("$schema".to_string(), json!("https://json-schema.org/draft/2020-12/schema"));
+    document.insert("title".to_string(), json!(name));
+    if let Value::Object(schema) = schema {
+        document.extend(schema);
+    }
+    if !defs.is_empty() {
+        document.insert("$defs".to_string(), Value::Object(defs));
+    }
+
+    let mut document = Value::Object(document);
+    let defined = document.clone();
+    resolve(&mut document, &name, &defined);
+    let document = serde_json::to_string_pretty(&document).expect("schemas are valid JSON");
+
+    let ident = definition.ident;
+    let (impl_generics, ty_generics, where_clause) = definition.generics.split_for_impl();
+
+    quote!(
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// JSON Schema of this type and every type nested in it
+            pub fn json_schema() -> &'static str {
+                #document
+            }
+        }
+    )
+}
+
+/// Points references to the root at the document itself, and allows any value for references to
+/// types that aren't defined in the tree, such as generic parameters
+fn resolve(schema: &mut Value, root: &str, document: &Value) {
+    match schema {
+        Value::Object(object) => {
+            if let Some(Value::String(reference)) = object.get("$ref") {
+                let name = reference.trim_start_matches("#/$defs/");
+                if name == root {
+                    *schema = json!({ "$ref": "#" });
+                } else if document.pointer(&format!("/$defs/{name}")).is_none() {
+                    *schema = json!({});
+                }
+                return;
+            }
+            object.values_mut().for_each(|value| resolve(value, root, document));
+        }
+        Value::Array(values) => values.iter_mut().for_each(|value| resolve(value, root, document)),
+        _ => {}
+    }
+}
+
+/// The schema of the serialized form of a definition
+fn schema(definition: &Definition) -> Value {
+    let serde = SerdeAttrs::parse(definition.attrs);
+
+    let mut schema = match definition.body {
+        UnpackedBody::Struct(UnpackedFields::Named(fields)) => object(fields, serde.rename_all.as_deref()),
+        UnpackedBody::Struct(UnpackedFields::Unnamed(fields)) => tuple(fields),
+        UnpackedBody::Struct(UnpackedFields::Unit) => json!({ "type": "null" }),
+        UnpackedBody::Enum(variants) => {
+            let variants: Vec<&UnpackedVariant> = variants
+                .iter()
+                .filter(|variant| !SerdeAttrs::parse(&variant.attrs).skip)
+                .collect();
+            let name = |variant: &UnpackedVariant| {
+                let serde_variant = SerdeAttrs::parse(&variant.attrs);
+                serde_variant.variant_name(&variant.ident.unraw().to_string(), serde.rename_all.as_deref())
+            };
+
+            // externally tagged unit variants are serialized as their name
+            let unit = variants.iter().all(|variant| matches!(variant.fields, UnpackedFields::Unit));
+            match unit && matches!(EnumRepr::from(&serde), EnumRepr::External) {
+                true => json!({
+                    "type": "string",
+                    "enum": variants.iter().map(|variant| name(variant)).collect::<Vec<_>>(),
+                }),
+                false => json!({
+                    "oneOf": variants
+                        .iter()
+                        .map(|variant| self::variant(variant, &name(variant), &serde))
+                        .collect::<Vec<_>>(),
+                }),
+            }
+        }
+    };
+
+    describe(&mut schema, definition.attrs);
+    schema
+}
+
+/// An object with a property for every field, flattened fields are combined with `allOf`
+fn object(fields: &[UnpackedField], rename_all: Option<&str>) -> Value {
+    let mut properties = Map::new();
+    let mut required = vec![];
+    let mut flattened = vec![];
+
+    for field in fields {
+        let serde = SerdeAttrs::parse(&field.attrs);
+        let Some(ident) = &field.ident else {
+            continue;
+        };
+        if serde.skip {
+            continue;
+        }
+        if serde.flatten {
+            flattened.push(ty(&field.ty));
+            continue;
+        }
+
+        let key = serde.field_name(&ident.unraw().to_string(), rename_all);
+        let mut property = ty(&field.ty);
+        describe(&mut property, &field.attrs);
+
+        if crate::unpack::option_inner(&field.ty).is_none() {
+            required.push(key.clone());
+        }
+        properties.insert(key, property);
+    }
+
+    let mut object = json!({ "type": "object", "properties": properties });
+    if !required.is_empty() {
+        object["required"] = json!(required);
+    }
+    if !flattened.is_empty() {
+        object["allOf"] = json!(flattened);
+    }
+    object
+}
+
+/// The fields of a tuple struct or variant, a single field is serialized as its value
+fn tuple(fields: &[UnpackedField]) -> Value {
+    match fields {
+        [field] => ty(&field.ty),
+        fields => tuple_of(fields.iter().map(|field| ty(&field.ty)).collect()),
+    }
+}
+
+fn tuple_of(items: Vec<Value>) -> Value {
+    let len = items.len();
+    json!({ "type": "array", "prefixItems": items, "minItems": len, "maxItems": len })
+}
+
+/// A variant of an enum, in the representation chosen with `tag`, `content` or `untagged`
+fn variant(variant: &UnpackedVariant, name: &str, enum_serde: &SerdeAttrs) -> Value {
+    let serde = SerdeAttrs::parse(&variant.attrs);
+
+    let payload = match &variant.fields {
+        UnpackedFields::Named(fields) => Some(object(fields, serde.rename_all.as_deref())),
+        UnpackedFields::Unnamed(fields) => Some(tuple(fields)),
+        UnpackedFields::Unit => None,
+    };
+    let tagged = |key: &str, value: Value| {
+        json!({ "type": "object", "properties": { key: value }, "required": [key] })
+    };
+
+    let mut schema = match EnumRepr::from(enum_serde) {
+        EnumRepr::Untagged => payload.unwrap_or_else(|| json!({ "type": "null" })),
+        EnumRepr::Adjacent { tag, content } => {
+            let mut schema = tagged(tag, json!({ "const": name }));
+            if let Some(payload) = payload {
+                schema["properties"][content] = payload;
+                schema["required"] = json!([tag, content]);
+            }
+            schema
+        }
+        EnumRepr::Internal { tag } => match payload {
+            // the tag is a property of the payload
+            Some(mut payload) if payload.get("properties").is_some() => {
+                payload["properties"][tag] = json!({ "const": name });
+                let required = payload["required"].as_array().cloned().unwrap_or_default();
+                payload["required"] = json!([vec![json!(tag)], required].concat());
+                payload
+            }
+            Some(payload) => json!({ "allOf": [tagged(tag, json!({ "const": name })), payload] }),
+            None => tagged(tag, json!({ "const": name })),
+        },
+        EnumRepr::External => match payload {
+            Some(payload) => {
+                let mut schema = tagged(name, payload);
+                schema["additionalProperties"] = json!(false);
+                schema
+            }
+            None => json!({ "const": name }),
+        },
+    };
+
+    describe(&mut schema, &variant.attrs);
+    schema
+}
+
+/// The schema of a Rust type, in its serialized form
+fn ty(ty: &Type) -> Value {
+    match classify(ty) {
+        TypeKind::Primitive(Primitive::Bool) => json!({ "type": "boolean" }),
+        TypeKind::Primitive(Primitive::Char) => json!({ "type": "string", "minLength": 1, "maxLength": 1 }),
+        TypeKind::Primitive(Primitive::String) => json!({ "type": "string" }),
+        TypeKind::Primitive(Primitive::Int { signed: false, .. }) => json!({ "type": "integer", "minimum": 0 }),
+        TypeKind::Primitive(Primitive::Int { signed: true, .. }) => json!({ "type": "integer" }),
+        TypeKind::Primitive(Primitive::Float { .. }) => json!({ "type": "number" }),
+        TypeKind::Unit => json!({ "type": "null" }),
+        TypeKind::Optional(inner) => nullable(self::ty(inner)),
+        TypeKind::Sequence(inner) => json!({ "type": "array", "items": self::ty(inner) }),
+        TypeKind::Set(inner) => json!({ "type": "array", "items": self::ty(inner), "uniqueItems": true }),
+        TypeKind::Map(_, value) => json!({ "type": "object", "additionalProperties": self::ty(value) }),
+        TypeKind::Tuple(types) => tuple_of(types.into_iter().map(self::ty).collect()),
+        // resolved once the whole tree is known
+        TypeKind::Named(name, _) => json!({ "$ref": format!("#/$defs/{name}") }),
+        TypeKind::Unknown => json!({}),
+    }
+}
+
+/// Allows `null` in addition to the values of `schema`
+fn nullable(mut schema: Value) -> Value {
+    match schema.get("type") {
+        Some(Value::String(ty)) => {
+            schema["type"] = json!([ty, "null"]);
+            schema
+        }
+        _ => json!({ "anyOf": [schema, { "type": "null" }] }),
+    }
+}
+
+/// Adds the doc comments as the `description` of a schema
+fn describe(schema: &mut Value, attrs: &[Attribute]) {
+    let lines = docs(attrs);
+
+    // references can't have siblings in older drafts, and the referenced schema has its own
+    if lines.is_empty() || schema.get("$ref").is_some() {
+        return;
+    }
+    if let Value::Object(schema) = schema {
+        schema.insert("description".to_string(), json!(lines.join("\n")));
+    }
+}
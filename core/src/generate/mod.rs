@@ -0,0 +1,209 @@
+//! Items generated next to a definition, such as trait implementations.
+//!
+//! Every generator receives the [`Definition`] after its fields have been unpacked,
+//! and decides on its own, usually from the definition's [`Directives`], whether to emit anything.
+
+use crate::attributes::Attribute;
+use crate::directives::Directives;
+use crate::twin::Twins;
+use crate::registry::DefinitionKind;
+use crate::serde_attrs::SerdeAttrs;
+use crate::unpack::{UnpackedField, UnpackedFields, UnpackedVariant};
+use proc_macro2::TokenStream;
+use syn::ext::IdentExt;
+use syn::{Expr, ExprLit, GenericArgument, Generics, Ident, Lit, Meta, PathArguments, PathSegment, Type, TypePath, Visibility};
+
+mod accessors;
+mod builder;
+mod constructor;
+mod default;
+mod from_variants;
+mod inspect;
+#[cfg(feature = "json")]
+pub(crate) mod json_schema;
+mod partial;
+pub(crate) mod proto;
+mod shape;
+mod string_values;
+pub(crate) mod typescript;
+mod views;
+
+/// A definition after unpacking
+pub(crate) struct Definition<'a> {
+    /// The attributes the definition is emitted with
+    pub attrs: &'a [Attribute],
+    pub ident: &'a Ident,
+    pub vis: &'a Visibility,
+    pub generics: &'a Generics,
+    pub directives: &'a Directives,
+    /// The twins of the definition, see [`crate::twin`]
+    pub twins: &'a Twins,
+    pub body: &'a UnpackedBody,
+}
+
+/// The body of a definition after unpacking
+pub(crate) enum UnpackedBody {
+    Struct(UnpackedFields),
+    Enum(Vec<UnpackedVariant>),
+}
+
+/// Generates every item that belongs next to the definition
+pub(crate) fn generate(definition: &Definition) -> TokenStream {
+    let mut items = TokenStream::new();
+
+    items.extend(default::generate(definition));
+    items.extend(builder::generate(definition));
+    items.extend(constructor::generate(definition));
+    items.extend(accessors::generate(definition));
+    items.extend(from_variants::generate(definition));
+    items.extend(inspect::generate(definition));
+    items.extend(partial::generate(definition));
+    items.extend(string_values::generate(definition));
+    items.extend(views::generate(definition));
+    items.extend(shape::generate(definition));
+
+    items
+}
+
+/// The lines of the doc comments in `attrs`
+pub(crate) fn docs(attrs: &[Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(doc) if doc.path.is_ident("doc") => match &doc.value {
+                Expr::Lit(ExprLit { lit: Lit::Str(line), .. }) => Some(line.value().trim().to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// A Rust type, as the generators of serialized forms tell types apart
+pub(crate) enum TypeKind<'a> {
+    Primitive(Primitive),
+    /// `()`
+    Unit,
+    /// `Option<T>`
+    Optional(&'a Type),
+    /// `Vec<T>`, `VecDeque<T>`, `LinkedList<T>`, arrays and slices
+    Sequence(&'a Type),
+    /// `HashSet<T>` and `BTreeSet<T>`
+    Set(&'a Type),
+    /// `HashMap<K, V>` and `BTreeMap<K, V>`
+    Map(&'a Type, &'a Type),
+    Tuple(Vec<&'a Type>),
+    /// Any other type, such as the ones defined in the tree, by name with its type arguments
+    Named(String, Vec<&'a Type>),
+    /// Types without a serialized form, such as function pointers and trait objects
+    Unknown,
+}
+
+/// A type serialized as a single value
+#[derive(Clone, Copy)]
+pub(crate) enum Primitive {
+    Bool,
+    Char,
+    /// `String`, `str`, `PathBuf` and `Path`
+    String,
+    /// `isize` and `usize` have 64 `bits`
+    Int { signed: bool, bits: u32 },
+    Float { bits: u32 },
+}
+
+/// Classifies `ty` by its serialized form. References and smart pointers, such as `Box` and
+/// `Cow`, are serialized as the type they point to, so they are classified as that type.
+pub(crate) fn classify(ty: &Type) -> TypeKind<'_> {
+    match ty {
+        Type::Reference(reference) => classify(&reference.elem),
+        Type::Paren(paren) => classify(&paren.elem),
+        Type::Group(group) => classify(&group.elem),
+        Type::Array(array) => TypeKind::Sequence(&array.elem),
+        Type::Slice(slice) => TypeKind::Sequence(&slice.elem),
+        Type::Tuple(tuple) if tuple.elems.is_empty() => TypeKind::Unit,
+        Type::Tuple(tuple) => TypeKind::Tuple(tuple.elems.iter().collect()),
+        Type::Path(path) if path.qself.is_none() => {
+            let Some((segment, args)) = last_segment(path) else {
+                return TypeKind::Unknown;
+            };
+            let name = segment.ident.unraw().to_string();
+
+            let int = |signed, bits| TypeKind::Primitive(Primitive::Int { signed, bits });
+            match (name.as_str(), args.as_slice()) {
+                ("bool", _) => TypeKind::Primitive(Primitive::Bool),
+                ("char", _) => TypeKind::Primitive(Primitive::Char),
+                ("String" | "str" | "PathBuf" | "Path", _) => TypeKind::Primitive(Primitive::String),
+                ("i8", _) => int(true, 8),
+                ("i16", _) => int(true, 16),
+                ("i32", _) => int(true, 32),
+                ("i64" | "isize", _) => int(true, 64),
+                ("i128", _) => int(true, 128),
+                ("u8", _) => int(false, 8),
+                ("u16", _) => int(false, 16),
+                ("u32", _) => int(false, 32),
+                ("u64" | "usize", _) => int(false, 64),
+                ("u128", _) => int(false, 128),
+                ("f32", _) => TypeKind::Primitive(Primitive::Float { bits: 32 }),
+                ("f64", _) => TypeKind::Primitive(Primitive::Float { bits: 64 }),
+                ("Box" | "Rc" | "Arc" | "Cow" | "RefCell" | "Cell", [.., inner]) => classify(inner),
+                ("Option", [inner]) => TypeKind::Optional(inner),
+                ("Vec" | "VecDeque" | "LinkedList", [inner]) => TypeKind::Sequence(inner),
+                ("HashSet" | "BTreeSet", [inner]) => TypeKind::Set(inner),
+                ("HashMap" | "BTreeMap", [key, value]) => TypeKind::Map(key, value),
+                _ => TypeKind::Named(name, args),
+            }
+        }
+        _ => TypeKind::Unknown,
+    }
+}
+
+/// The last segment of a type path and the types among its generic arguments:
+/// `std::collections::HashMap<String, u8>` -> `HashMap` and `[String, u8]`
+fn last_segment(path: &TypePath) -> Option<(&PathSegment, Vec<&Type>)> {
+    let segment = path.path.segments.last()?;
+    let args = match &segment.arguments {
+        PathArguments::AngleBracketed(arguments) => arguments
+            .args
+            .iter()
+            .filter_map(|argument| match argument {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    };
+
+    Some((segment, args))
+}
+
+/// How serde represents the variants of an enum, chosen with `tag`, `content` or `untagged`
+pub(crate) enum EnumRepr<'a> {
+    /// `{ "Variant": payload }`, or `"Variant"` for unit variants
+    External,
+    /// `#[serde(tag = "t")]`: `{ "t": "Variant", ..payload }`
+    Internal { tag: &'a str },
+    /// `#[serde(tag = "t", content = "c")]`: `{ "t": "Variant", "c": payload }`
+    Adjacent { tag: &'a str, content: &'a str },
+    /// `#[serde(untagged)]`: the payload alone
+    Untagged,
+}
+
+impl<'a> From<&'a SerdeAttrs> for EnumRepr<'a> {
+    fn from(serde: &'a SerdeAttrs) -> Self {
+        match (&serde.tag, &serde.content, serde.untagged) {
+            (_, _, true) => EnumRepr::Untagged,
+            (Some(tag), Some(content), _) => EnumRepr::Adjacent { tag, content },
+            (Some(tag), None, _) => EnumRepr::Internal { tag },
+            (None, _, false) => EnumRepr::External,
+        }
+    }
+}
+
+/// Returns `true` if the field is a struct with named fields defined directly as its type,
+/// which has the same twins as the struct of the field, see [`crate::twin`]
+fn has_twin(field: &UnpackedField) -> bool {
+    field
+        .inline
+        .as_ref()
+        .is_some_and(|inline| inline.kind == DefinitionKind::NamedStruct && inline.ty == field.ty)
+}
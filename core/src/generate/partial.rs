@@ -0,0 +1,49 @@
+use crate::generate::{has_twin, Definition, UnpackedBody};
+use crate::twin::twin_ident;
+use crate::unpack::UnpackedFields;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Generates `apply` for structs with a partial twin, which overwrites every field
+/// that is set in the twin. Nested structs with twins of their own are patched recursively.
+pub(super) fn generate(definition: &Definition) -> TokenStream {
+    let Some(suffix) = &definition.twins.partial else {
+        return TokenStream::new();
+    };
+    let partial = twin_ident(definition.ident, suffix);
+
+    let UnpackedBody::Struct(UnpackedFields::Named(fields)) = definition.body else {
+        return TokenStream::new();
+    };
+
+    let statements = fields.iter().filter_map(|field| {
+        let name = field.ident.as_ref()?;
+
+        Some(if field.flatten {
+            quote!(self.#name.apply(patch.#name);)
+        } else if has_twin(field) {
+            quote!(
+                if let ::core::option::Option::Some(patch) = patch.#name {
+                    self.#name.apply(patch);
+                }
+            )
+        } else {
+            quote!(
+                if let ::core::option::Option::Some(value) = patch.#name {
+                    self.#name = value;
+                }
+            )
+        })
+    });
+
+    let ident = definition.ident;
+    let (impl_generics, ty_generics, where_clause) = definition.generics.split_for_impl();
+
+    quote!(
+        impl #impl_generics #ident #ty_generics #where_clause {
+            pub fn apply(&mut self, patch: #partial #ty_generics) {
+                #(#statements)*
+            }
+        }
+    )
+}
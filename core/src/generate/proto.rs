@@ -0,0 +1,351 @@
+use crate::attributes::Attribute;
+use crate::case::to_snake_case;
+use crate::directives::is_directive;
+use crate::generate::{classify, docs, Definition, Primitive, TypeKind, UnpackedBody};
+use crate::unpack::{UnpackedField, UnpackedFields, UnpackedVariant};
+use proc_macro2::{Span, TokenStream};
+use proc_macro_error::emit_error;
+use quote::{quote, ToTokens};
+use std::cell::RefCell;
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{LitInt, Meta, Type};
+
+/// Field numbers reserved by Protobuf
+const RESERVED: std::ops::RangeInclusive<u32> = 19000..=19999;
+/// The largest field number
+const MAX: u32 = 536_870_911;
+
+/// Generates `const PROTO` for the definition `#[nest::proto]` was written on, with every
+/// definition nested in it declared as a nested message or enum.
+///
+/// `messages` collects the declarations of the nested definitions, which are unpacked before
+/// the definition itself. The ones from `nested` on are nested in this definition.
+pub(crate) fn generate(
+    definition: &Definition,
+    messages: &RefCell<Vec<String>>,
+    nested: usize,
+    root: bool,
+) -> TokenStream {
+    let nested: Vec<String> = messages.borrow_mut().drain(nested..).collect();
+    let declaration = declaration(definition, nested);
+
+    if !root {
+        messages.borrow_mut().push(declaration);
+        return TokenStream::new();
+    }
+
+    let proto = format!("syntax = \"proto3\";\n\n{declaration}\n");
+    let ident = definition.ident;
+    let (impl_generics, ty_generics, where_clause) = definition.generics.split_for_impl();
+
+    quote!(
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// Protobuf declaration of this type, with every type nested in it
+            pub const PROTO: &'static str = #proto;
+        }
+    )
+}
+
+/// Splits `#[nest::tag(n)]` off the attributes of a field or variant
+pub(crate) fn take_tag(attrs: Vec<Attribute>, proto: bool) -> (Vec<Attribute>, Option<LitInt>) {
+    let (tags, attrs): (Vec<_>, Vec<_>) = attrs.into_iter().partition(|attr| is_tag(&attr.meta));
+
+    let mut tag = None;
+    for attr in tags {
+        let parsed = match &attr.meta {
+            Meta::List(list) => list.parse_args::<LitInt>().ok(),
+            _ => None,
+        };
+        match (parsed, &tag) {
+            (None, _) => emit_error!(
+                attr.meta.span(),
+                "`#[nest::tag]` expects a field number";
+                help = "for example `#[nest::tag(4)]`"
+            ),
+            (Some(_), Some(_)) => emit_error!(attr.meta.span(), "the field number is already set"),
+            (Some(_), None) if !proto => emit_error!(
+                attr.meta.span(),
+                "`#[nest::tag]` requires `#[nest::proto]`";
+                help = "add `#[nest::proto]` to the outermost definition"
+            ),
+            (Some(parsed), None) => tag = Some(parsed),
+        }
+    }
+
+    (attrs, tag)
+}
+
+fn is_tag(meta: &Meta) -> bool {
+    let path = meta.path();
+    is_directive(path) && path.segments[1].ident == "tag"
+}
+
+/// The `message` or `enum` declaring a definition, with `nested` declared inside of it
+fn declaration(definition: &Definition, nested: Vec<String>) -> String {
+    let name = definition.ident.unraw().to_string();
+
+    let declaration = match definition.body {
+        UnpackedBody::Struct(UnpackedFields::Named(fields) | UnpackedFields::Unnamed(fields)) => {
+            message(&name, nested, self::fields(fields))
+        }
+        UnpackedBody::Struct(UnpackedFields::Unit) => message(&name, nested, vec![]),
+        UnpackedBody::Enum(variants) => {
+            let unit = variants.iter().all(|variant| matches!(variant.fields, UnpackedFields::Unit));
+            match unit && nested.is_empty() {
+                true => enumeration(&name, variants),
+                false => one_of(&name, nested, variants),
+            }
+        }
+    };
+
+    [comments(definition.attrs), vec![declaration]].concat().join("\n")
+}
+
+/// `message Name { ... }`, with the nested declarations before the fields
+fn message(name: &str, nested: Vec<String>, fields: Vec<String>) -> String {
+    if nested.is_empty() && fields.is_empty() {
+        return format!("message {name} {{}}");
+    }
+
+    let mut blocks = nested;
+    if !fields.is_empty() {
+        blocks.push(fields.join("\n"));
+    }
+    format!("message {name} {{\n{}\n}}", indent(&blocks.join("\n\n")))
+}
+
+/// The fields of a message, numbered from 1 or from the last `#[nest::tag(n)]`
+fn fields(fields: &[UnpackedField]) -> Vec<String> {
+    let mut numbers = Numbers::new(1);
+
+    fields
+        .iter()
+        .enumerate()
+        .filter_map(|(index, field)| {
+            let number = numbers.next(field.tag.as_ref(), field_span(field));
+            let name = match &field.ident {
+                Some(ident) => ident.unraw().to_string(),
+                None => format!("field_{index}"),
+            };
+            let ty = ty(&field.ty)?;
+
+            let line = format!("{ty} {name} = {number};");
+            Some([comments(&field.attrs), vec![line]].concat().join("\n"))
+        })
+        .collect()
+}
+
+fn field_span(field: &UnpackedField) -> Span {
+    match &field.ident {
+        Some(ident) => ident.span(),
+        None => field.ty.span(),
+    }
+}
+
+/// A unit enum becomes an `enum`, with its values prefixed by the name of the enum
+fn enumeration(name: &str, variants: &[UnpackedVariant]) -> String {
+    let mut numbers = Numbers::new(0);
+    let prefix = to_snake_case(name).to_uppercase();
+
+    let values: Vec<String> = variants
+        .iter()
+        .enumerate()
+        .map(|(index, variant)| {
+            let number = numbers.next(variant.tag.as_ref(), variant.ident.span());
+            if index == 0 && number != 0 {
+                emit_error!(
+                    variant.ident, "the first value of a Protobuf enum must be 0";
+                    note = "Protobuf uses it as the default value"
+                );
+            }
+            let value = to_snake_case(&variant.ident.unraw().to_string()).to_uppercase();
+
+            let value = format!("{prefix}_{value} = {number};");
+            [comments(&variant.attrs), vec![value]].concat().join("\n")
+        })
+        .collect();
+
+    match values.is_empty() {
+        true => format!("enum {name} {{}}"),
+        false => format!("enum {name} {{\n{}\n}}", indent(&values.join("\n"))),
+    }
+}
+
+/// An enum with payloads becomes a message with a `oneof`. Variants with named fields or
+/// without fields are declared as messages, a variant with one field uses its type directly.
+fn one_of(name: &str, mut nested: Vec<String>, variants: &[UnpackedVariant]) -> String {
+    let mut numbers = Numbers::new(1);
+
+    let alternatives: Vec<String> = variants
+        .iter()
+        .filter_map(|variant| {
+            let number = numbers.next(variant.tag.as_ref(), variant.ident.span());
+            let variant_name = variant.ident.unraw().to_string();
+
+            let ty = match &variant.fields {
+                UnpackedFields::Unnamed(fields) if fields.len() == 1 => {
+                    let ty = ty(&fields[0].ty)?;
+                    if ty.starts_with("repeated ") || ty.starts_with("optional ") || ty.starts_with("map<") {
+                        emit_error!(
+                            fields[0].ty, "`{}` cannot be a `oneof` alternative", fields[0].ty.to_token_stream();
+                            help = "define a struct for the variant instead"
+                        );
+                        return None;
+                    }
+                    ty
+                }
+                UnpackedFields::Named(fields) | UnpackedFields::Unnamed(fields) => {
+                    nested.push(message(&variant_name, vec![], self::fields(fields)));
+                    variant_name.clone()
+                }
+                UnpackedFields::Unit => {
+                    nested.push(message(&variant_name, vec![], vec![]));
+                    variant_name.clone()
+                }
+            };
+
+            let alternative = format!("{ty} {} = {number};", to_snake_case(&variant_name));
+            Some([comments(&variant.attrs), vec![alternative]].concat().join("\n"))
+        })
+        .collect();
+
+    let one_of = match alternatives.is_empty() {
+        true => format!("oneof {} {{}}", to_snake_case(name)),
+        false => format!("oneof {} {{\n{}\n}}", to_snake_case(name), indent(&alternatives.join("\n"))),
+    };
+    message(name, nested, vec![one_of])
+}
+
+/// The type of a field with its label, `None` if Protobuf can't represent it
+fn ty(ty: &Type) -> Option<String> {
+    match classify(ty) {
+        TypeKind::Primitive(primitive) => scalar(primitive).map(str::to_string).or_else(|| unsupported(ty)),
+        TypeKind::Sequence(inner) if is_u8(inner) => Some("bytes".to_string()),
+        TypeKind::Optional(inner) => {
+            let inner = self::ty(inner)?;
+            match inner.starts_with("repeated ") || inner.starts_with("map<") {
+                // empty collections already mean no value
+                true => Some(inner),
+                false => labeled("optional", inner, ty),
+            }
+        }
+        TypeKind::Sequence(inner) | TypeKind::Set(inner) => labeled("repeated", self::ty(inner)?, ty),
+        TypeKind::Map(key, value) => {
+            // floats can't be keys
+            let key = match classify(key) {
+                TypeKind::Primitive(Primitive::Float { .. }) => None,
+                TypeKind::Primitive(primitive) => scalar(primitive),
+                _ => None,
+            };
+            let value = self::ty(value)?;
+            match (key, value.contains(' ') || value.starts_with("map<")) {
+                (Some(key), false) => Some(format!("map<{key}, {value}>")),
+                _ => unsupported(ty),
+            }
+        }
+        TypeKind::Named(name, args) if args.is_empty() => Some(name),
+        TypeKind::Unit | TypeKind::Tuple(_) | TypeKind::Named(..) | TypeKind::Unknown => unsupported(ty),
+    }
+}
+
+/// `repeated T` or `optional T`, which can't be combined with another label
+fn labeled(label: &str, inner: String, ty: &Type) -> Option<String> {
+    match inner.contains(' ') || inner.starts_with("map<") {
+        true => unsupported(ty),
+        false => Some(format!("{label} {inner}")),
+    }
+}
+
+/// The Protobuf scalar a Rust primitive is encoded as
+fn scalar(primitive: Primitive) -> Option<&'static str> {
+    let scalar = match primitive {
+        Primitive::String | Primitive::Char => "string",
+        Primitive::Bool => "bool",
+        Primitive::Int { signed: true, bits: ..=32 } => "int32",
+        Primitive::Int { signed: true, bits: 64 } => "int64",
+        Primitive::Int { signed: false, bits: ..=32 } => "uint32",
+        Primitive::Int { signed: false, bits: 64 } => "uint64",
+        Primitive::Float { bits: 32 } => "float",
+        Primitive::Float { .. } => "double",
+        Primitive::Int { .. } => return None,
+    };
+    Some(scalar)
+}
+
+fn is_u8(ty: &Type) -> bool {
+    matches!(classify(ty), TypeKind::Primitive(Primitive::Int { signed: false, bits: 8 }))
+}
+
+fn unsupported(ty: &Type) -> Option<String> {
+    emit_error!(
+        ty, "`{}` has no Protobuf equivalent", ty.to_token_stream();
+        note = "supported types are primitives, `String`, `Vec`, `Option`, maps and the types defined in the tree"
+    );
+    None
+}
+
+/// Assigns field numbers in order, continuing after the numbers set with `#[nest::tag(n)]`
+struct Numbers {
+    next: u32,
+    /// The smallest number, 1 for fields and 0 for enum values
+    first: u32,
+    used: Vec<u32>,
+}
+
+impl Numbers {
+    fn new(first: u32) -> Self {
+        Numbers { next: first, first, used: vec![] }
+    }
+
+    fn next(&mut self, tag: Option<&LitInt>, span: Span) -> u32 {
+        // enum values aren't field numbers, and can use the reserved ones
+        let valid = |number: &u32| {
+            (self.first..=MAX).contains(number) && (self.first == 0 || !RESERVED.contains(number))
+        };
+        let number = match tag.map(LitInt::base10_parse::<u32>) {
+            Some(Ok(number)) if valid(&number) => number,
+            Some(_) => {
+                let tag = tag.expect("matched `Some`");
+                emit_error!(
+                    tag, "`{}` is not a valid field number", tag;
+                    note = "field numbers are between {} and {}, except {} to {}",
+                        self.first, MAX, RESERVED.start(), RESERVED.end()
+                );
+                self.next
+            }
+            None => self.next,
+        };
+
+        if self.used.contains(&number) {
+            emit_error!(
+                span, "the field number {} is already used", number;
+                help = "set another number with `#[nest::tag(n)]`"
+            );
+        }
+        self.used.push(number);
+        self.next = number.saturating_add(1);
+        number
+    }
+}
+
+/// Doc comments as `//` comments
+fn comments(attrs: &[Attribute]) -> Vec<String> {
+    docs(attrs)
+        .into_iter()
+        .map(|line| match line.is_empty() {
+            true => "//".to_string(),
+            false => format!("// {line}"),
+        })
+        .collect()
+}
+
+fn indent(text: &str) -> String {
+    text.lines()
+        .map(|line| match line.is_empty() {
+            true => String::new(),
+            false => format!("  {line}"),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,87 @@
+use crate::generate::{Definition, UnpackedBody};
+use crate::unpack::{UnpackedField, UnpackedFields};
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::ext::IdentExt;
+use syn::Type;
+
+/// Generates `const SHAPE: nestify::Shape` for definitions with `#[nest::shape]`
+pub(super) fn generate(definition: &Definition) -> TokenStream {
+    if !definition.directives.shape {
+        return TokenStream::new();
+    }
+
+    let ident = definition.ident;
+    let (impl_generics, ty_generics, where_clause) = definition.generics.split_for_impl();
+    let name = ident.unraw().to_string();
+
+    let body = match definition.body {
+        UnpackedBody::Struct(fields) => {
+            let fields = self::fields(fields);
+            quote!(::nestify::shape::Body::Struct(#fields))
+        }
+        UnpackedBody::Enum(variants) => {
+            let variants = variants.iter().map(|variant| {
+                let name = variant.ident.unraw().to_string();
+                let fields = fields(&variant.fields);
+                quote!(::nestify::shape::Variant { name: #name, fields: #fields })
+            });
+            quote!(::nestify::shape::Body::Enum(&[#(#variants),*]))
+        }
+    };
+
+    quote!(
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// The shape of this type, with the shapes of the types nested in it
+            pub const SHAPE: ::nestify::Shape = ::nestify::Shape { name: #name, body: #body };
+        }
+    )
+}
+
+fn fields(fields: &UnpackedFields) -> TokenStream {
+    match fields {
+        UnpackedFields::Named(fields) => {
+            let fields = fields.iter().map(field);
+            quote!(::nestify::shape::Fields::Named(&[#(#fields),*]))
+        }
+        UnpackedFields::Unnamed(fields) => {
+            let fields = fields.iter().map(field);
+            quote!(::nestify::shape::Fields::Unnamed(&[#(#fields),*]))
+        }
+        UnpackedFields::Unit => quote!(::nestify::shape::Fields::Unit),
+    }
+}
+
+fn field(field: &UnpackedField) -> TokenStream {
+    let name = match &field.ident {
+        Some(ident) => {
+            let ident = ident.unraw().to_string();
+            quote!(::core::option::Option::Some(#ident))
+        }
+        None => quote!(::core::option::Option::None),
+    };
+    let ty = type_name(&field.ty);
+    let inline = match &field.inline {
+        Some(inline) if inline.directives.shape => {
+            let ty = &inline.ty;
+            quote!(::core::option::Option::Some(&<#ty>::SHAPE))
+        }
+        _ => quote!(::core::option::Option::None),
+    };
+
+    quote!(::nestify::shape::Field { name: #name, ty: #ty, inline: #inline })
+}
+
+/// The type as it would be written, `Option<Vec<String>>` rather than `Option < Vec < String > >`
+fn type_name(ty: &Type) -> String {
+    ty.to_token_stream()
+        .to_string()
+        // `field?: T` is emitted with the full path
+        .replace(":: core :: option :: Option", "Option")
+        .replace(" :: ", "::")
+        .replace(" <", "<")
+        .replace("< ", "<")
+        .replace(" >", ">")
+        .replace(" ,", ",")
+        .replace("& ", "&")
+}
@@ -0,0 +1,61 @@
+use crate::generate::{Definition, UnpackedBody};
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Generates the string conversions of enums whose variants have string values:
+/// `Display`, `FromStr`, `AsRef<str>` and an `ALL` constant with every variant.
+pub(super) fn generate(definition: &Definition) -> TokenStream {
+    let UnpackedBody::Enum(variants) = definition.body else {
+        return TokenStream::new();
+    };
+
+    let Some(values) = variants
+        .iter()
+        .map(|variant| variant.value.as_ref())
+        .collect::<Option<Vec<_>>>()
+    else {
+        return TokenStream::new();
+    };
+
+    if values.is_empty() {
+        return TokenStream::new();
+    }
+
+    let ident = definition.ident;
+    let (impl_generics, ty_generics, where_clause) = definition.generics.split_for_impl();
+    let variants: Vec<_> = variants.iter().map(|variant| &variant.ident).collect();
+    let count = variants.len();
+    let unknown = format!("unknown `{}` value `{{}}`", ident);
+
+    quote!(
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// Every variant, in declaration order
+            pub const ALL: [Self; #count] = [#(Self::#variants),*];
+        }
+
+        impl #impl_generics ::core::convert::AsRef<str> for #ident #ty_generics #where_clause {
+            fn as_ref(&self) -> &str {
+                match self {
+                    #(Self::#variants => #values,)*
+                }
+            }
+        }
+
+        impl #impl_generics ::core::fmt::Display for #ident #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.write_str(::core::convert::AsRef::<str>::as_ref(self))
+            }
+        }
+
+        impl #impl_generics ::core::str::FromStr for #ident #ty_generics #where_clause {
+            type Err = ::std::string::String;
+
+            fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                match s {
+                    #(#values => ::core::result::Result::Ok(Self::#variants),)*
+                    _ => ::core::result::Result::Err(::std::format!(#unknown, s)),
+                }
+            }
+        }
+    )
+}
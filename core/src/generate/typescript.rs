@@ -0,0 +1,208 @@
+use crate::generate::{classify, Definition, EnumRepr, Primitive, TypeKind, UnpackedBody};
+use crate::serde_attrs::SerdeAttrs;
+use crate::unpack::{UnpackedField, UnpackedFields, UnpackedVariant};
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::cell::RefCell;
+use syn::ext::IdentExt;
+use syn::Type;
+
+/// Generates `const TS_DECL` for definitions with `#[nest::typescript]`, and `const TS_DECLS`
+/// with the declarations of the whole tree for the definition the directive was written on.
+///
+/// `declarations` collects the declarations of the definitions nested in it, which are
+/// unpacked before the definition itself.
+pub(crate) fn generate(definition: &Definition, declarations: &RefCell<Vec<String>>, root: bool) -> TokenStream {
+    let declaration = declaration(definition);
+    declarations.borrow_mut().push(declaration.clone());
+
+    let ident = definition.ident;
+    let (impl_generics, ty_generics, where_clause) = definition.generics.split_for_impl();
+
+    let all = root.then(|| {
+        let declarations = declarations.borrow();
+        // the definition itself is declared first, then the definitions nested in it
+        let mut all = vec![declaration.as_str()];
+        for nested in &declarations[..declarations.len() - 1] {
+            if !all.contains(&nested.as_str()) {
+                all.push(nested);
+            }
+        }
+        let all = all.join("\n\n");
+
+        quote!(
+            /// TypeScript declarations of this type and every type nested in it
+            pub const TS_DECLS: &'static str = #all;
+        )
+    });
+
+    quote!(
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// TypeScript declaration of this type
+            pub const TS_DECL: &'static str = #declaration;
+            #all
+        }
+    )
+}
+
+/// The TypeScript declaration of the serialized form of a definition
+fn declaration(definition: &Definition) -> String {
+    let serde = SerdeAttrs::parse(definition.attrs);
+    let rename_all = serde.rename_all.as_deref();
+
+    let params: Vec<String> = definition
+        .generics
+        .type_params()
+        .map(|param| param.ident.to_string())
+        .collect();
+    let name = match params.is_empty() {
+        true => definition.ident.unraw().to_string(),
+        false => format!("{}<{}>", definition.ident.unraw(), params.join(", ")),
+    };
+
+    match definition.body {
+        UnpackedBody::Struct(UnpackedFields::Named(fields)) => {
+            let (members, flattened) = object(fields, rename_all);
+            let members: String = members.iter().map(|member| format!("  {member};\n")).collect();
+
+            match flattened.is_empty() {
+                true => format!("export interface {name} {{\n{members}}}"),
+                false => format!("export type {name} = {{\n{members}}} & {};", flattened.join(" & ")),
+            }
+        }
+        UnpackedBody::Struct(UnpackedFields::Unnamed(fields)) => {
+            format!("export type {name} = {};", tuple(fields))
+        }
+        UnpackedBody::Struct(UnpackedFields::Unit) => format!("export type {name} = null;"),
+        UnpackedBody::Enum(variants) => {
+            let variants: Vec<String> = variants
+                .iter()
+                .filter(|variant| !SerdeAttrs::parse(&variant.attrs).skip)
+                .map(|variant| self::variant(variant, &serde))
+                .collect();
+
+            match variants.is_empty() {
+                true => format!("export type {name} = never;"),
+                false => format!("export type {name} =\n  | {};", variants.join("\n  | ")),
+            }
+        }
+    }
+}
+
+/// The members of an object type, and the types of flattened fields
+fn object(fields: &[UnpackedField], rename_all: Option<&str>) -> (Vec<String>, Vec<String>) {
+    let mut members = vec![];
+    let mut flattened = vec![];
+
+    for field in fields {
+        let serde = SerdeAttrs::parse(&field.attrs);
+        let Some(ident) = &field.ident else {
+            continue;
+        };
+        if serde.skip {
+            continue;
+        }
+        if serde.flatten {
+            flattened.push(ts_type(&field.ty));
+            continue;
+        }
+
+        let key = key(&serde.field_name(&ident.unraw().to_string(), rename_all));
+        let member = match crate::unpack::option_inner(&field.ty) {
+            // skipped when `None`, instead of serialized as `null`
+            Some(inner) if serde.skip_serializing_if => format!("{key}?: {}", ts_type(inner)),
+            _ => format!("{key}: {}", ts_type(&field.ty)),
+        };
+        members.push(member);
+    }
+
+    (members, flattened)
+}
+
+/// The type of tuple fields, a single field is serialized as its value
+fn tuple(fields: &[UnpackedField]) -> String {
+    match fields {
+        [field] => ts_type(&field.ty),
+        fields => {
+            let types: Vec<String> = fields.iter().map(|field| ts_type(&field.ty)).collect();
+            format!("[{}]", types.join(", "))
+        }
+    }
+}
+
+/// A variant of an enum, in the representation chosen with `tag`, `content` or `untagged`
+fn variant(variant: &UnpackedVariant, enum_serde: &SerdeAttrs) -> String {
+    let serde = SerdeAttrs::parse(&variant.attrs);
+    let name = serde.variant_name(&variant.ident.unraw().to_string(), enum_serde.rename_all.as_deref());
+    let name = format!("{name:?}");
+
+    let payload = match &variant.fields {
+        UnpackedFields::Named(fields) => {
+            let (members, flattened) = object(fields, serde.rename_all.as_deref());
+            let object = format!("{{ {} }}", members.join("; "));
+            Some([vec![object], flattened].concat().join(" & "))
+        }
+        UnpackedFields::Unnamed(fields) => Some(tuple(fields)),
+        UnpackedFields::Unit => None,
+    };
+
+    match EnumRepr::from(enum_serde) {
+        EnumRepr::Untagged => payload.unwrap_or_else(|| "null".to_string()),
+        EnumRepr::Adjacent { tag, content } => match payload {
+            Some(payload) => format!("{{ {}: {name}; {}: {payload} }}", key(tag), key(content)),
+            None => format!("{{ {}: {name} }}", key(tag)),
+        },
+        EnumRepr::Internal { tag } => match payload {
+            Some(payload) => format!("({{ {}: {name} }} & {payload})", key(tag)),
+            None => format!("{{ {}: {name} }}", key(tag)),
+        },
+        EnumRepr::External => match payload {
+            Some(payload) => format!("{{ {name}: {payload} }}"),
+            None => name,
+        },
+    }
+}
+
+/// A property name, quoted unless it is a valid identifier
+fn key(name: &str) -> String {
+    let identifier = name.starts_with(|ch: char| ch.is_ascii_alphabetic() || ch == '_' || ch == '$')
+        && name.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '$');
+
+    match identifier {
+        true => name.to_string(),
+        false => format!("{name:?}"),
+    }
+}
+
+/// The TypeScript type of a Rust type, in its serialized form
+fn ts_type(ty: &Type) -> String {
+    match classify(ty) {
+        TypeKind::Primitive(Primitive::Bool) => "boolean".to_string(),
+        TypeKind::Primitive(Primitive::Char | Primitive::String) => "string".to_string(),
+        TypeKind::Primitive(Primitive::Int { .. } | Primitive::Float { .. }) => "number".to_string(),
+        TypeKind::Unit => "null".to_string(),
+        TypeKind::Optional(inner) => format!("{} | null", ts_type(inner)),
+        TypeKind::Sequence(inner) | TypeKind::Set(inner) => array_of(inner),
+        TypeKind::Map(key, value) => format!("Record<{}, {}>", ts_type(key), ts_type(value)),
+        TypeKind::Tuple(types) => {
+            let types: Vec<String> = types.into_iter().map(ts_type).collect();
+            format!("[{}]", types.join(", "))
+        }
+        TypeKind::Named(name, args) if args.is_empty() => name,
+        TypeKind::Named(name, args) => {
+            let args: Vec<String> = args.into_iter().map(ts_type).collect();
+            format!("{name}<{}>", args.join(", "))
+        }
+        TypeKind::Unknown => "unknown".to_string(),
+    }
+}
+
+/// `T[]`, with parentheses around unions
+fn array_of(ty: &Type) -> String {
+    let ty = ts_type(ty);
+
+    match ty.contains(' ') {
+        true => format!("({ty})[]"),
+        false => format!("{ty}[]"),
+    }
+}
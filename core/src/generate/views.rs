@@ -0,0 +1,68 @@
+use crate::generate::{has_twin, Definition, UnpackedBody};
+use crate::twin::twin_ident;
+use crate::unpack::{UnpackedField, UnpackedFields};
+use crate::views::{self, includes};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Ident;
+
+/// Generates conversions for structs with views: from the struct into each of its views,
+/// and between views where every field of one is also a field of the other.
+pub(super) fn generate(definition: &Definition) -> TokenStream {
+    let UnpackedBody::Struct(UnpackedFields::Named(fields)) = definition.body else {
+        return TokenStream::new();
+    };
+
+    let ident = definition.ident;
+    let twins = definition.twins;
+    let view_ident = |view: &Ident| twin_ident(ident, &views::suffix(view));
+
+    let from_original = twins
+        .views
+        .iter()
+        .map(|view| conversion(definition, quote!(#ident), &view_ident(view), fields, view));
+
+    let between_views = twins.conversions.iter().map(|(from, into)| {
+        let from = view_ident(from);
+        conversion(definition, quote!(#from), &view_ident(into), fields, into)
+    });
+
+    quote!(
+        #(#from_original)*
+        #(#between_views)*
+    )
+}
+
+/// `impl From<from> for into`, where `into` is the view `view` of the definition
+fn conversion(
+    definition: &Definition,
+    from: TokenStream,
+    into: &Ident,
+    fields: &[UnpackedField],
+    view: &Ident,
+) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = definition.generics.split_for_impl();
+
+    let values = fields
+        .iter()
+        .filter(|field| includes(&field.only, view))
+        .filter_map(|field| {
+            let name = field.ident.as_ref()?;
+
+            Some(if field.flatten || has_twin(field) {
+                quote!(#name: ::core::convert::Into::into(value.#name))
+            } else {
+                quote!(#name: value.#name)
+            })
+        });
+
+    quote!(
+        impl #impl_generics ::core::convert::From<#from #ty_generics> for #into #ty_generics #where_clause {
+            fn from(value: #from #ty_generics) -> Self {
+                Self {
+                    #(#values),*
+                }
+            }
+        }
+    )
+}
@@ -0,0 +1,147 @@
+use crate::attributes::FieldAttribute;
+use crate::special_data::{Body, BodyStruct, Special, SpecialField, SpecialFields};
+use crate::ty::SpecialType;
+use proc_macro_error::emit_error;
+use syn::Ident;
+
+/// Resolves struct inheritance `struct B: A { .. }` over the whole parsed tree,
+/// before anything is unpacked.
+///
+/// `B` starts with copies of the named fields of `A`, including the fields `A` inherits itself,
+/// unless `B` declares a field with the same name. Definitions in the copied fields are
+/// replaced by their name, since they are already defined by `A`.
+pub(crate) fn resolve(root: &mut Special) {
+    let mut bases = vec![];
+    root.visit(&mut |special| bases.push(Base::new(special)));
+
+    if bases.iter().all(|base| base.parent.is_none()) {
+        return;
+    }
+
+    root.visit(&mut |special| {
+        let Body::Struct(BodyStruct {
+            inherits: inherits @ Some(_),
+            fields: SpecialFields::Named(named),
+            ..
+        }) = &mut special.body
+        else {
+            return;
+        };
+        let (_, parent) = inherits.take().expect("matched `Some`");
+
+        let Some(inherited) = fields_of(&parent, &bases, &mut vec![&special.ident]) else {
+            return;
+        };
+
+        let own = std::mem::take(&mut named.named);
+        for field in inherited {
+            if !own.iter().any(|own| own.ident == field.ident) {
+                named.named.push(field);
+            }
+        }
+        named.named.extend(own);
+    });
+}
+
+/// A struct that can be inherited from, as it was parsed
+struct Base {
+    ident: Ident,
+    parent: Option<Ident>,
+    /// The named fields, with definitions replaced by their name, or `None` for
+    /// definitions that are not structs with named fields
+    fields: Option<Vec<SpecialField>>,
+}
+
+impl Base {
+    fn new(special: &Special) -> Self {
+        let (parent, fields) = match &special.body {
+            Body::Struct(BodyStruct { inherits, fields: SpecialFields::Named(named), .. }) => (
+                inherits.as_ref().map(|(_, parent)| parent.clone()),
+                Some(named.named.iter().flat_map(reference_fields).collect()),
+            ),
+            _ => (None, None),
+        };
+
+        Base {
+            ident: special.ident.clone(),
+            parent,
+            fields,
+        }
+    }
+}
+
+/// All named fields of the struct `ident`, including inherited ones.
+/// `chain` holds the structs that are inheriting from it, to detect cycles.
+fn fields_of<'a>(ident: &'a Ident, bases: &'a [Base], chain: &mut Vec<&'a Ident>) -> Option<Vec<SpecialField>> {
+    if chain.contains(&ident) {
+        emit_error!(ident.span(), "`{}` inherits from itself", ident);
+        return None;
+    }
+
+    let Some(base) = bases.iter().find(|base| base.ident == *ident) else {
+        emit_error!(ident.span(), "cannot find struct `{}` in this `nest!` invocation", ident);
+        return None;
+    };
+
+    let Some(own) = &base.fields else {
+        emit_error!(ident.span(), "`{}` is not a struct with named fields", ident);
+        return None;
+    };
+
+    let mut fields = match &base.parent {
+        Some(parent) => {
+            chain.push(ident);
+            let inherited = fields_of(parent, bases, chain)?;
+            chain.pop();
+
+            inherited
+                .into_iter()
+                .filter(|field| !own.iter().any(|own| own.ident == field.ident))
+                .collect()
+        }
+        None => vec![],
+    };
+
+    fields.extend(own.iter().map(copy_field));
+    Some(fields)
+}
+
+/// Copies of a field that do not define anything. A spread field is replaced by the fields
+/// it spreads, which keeps the same shape when serialized.
+fn reference_fields(field: &SpecialField) -> Vec<SpecialField> {
+    match (&field.spread, &field.ty) {
+        (
+            Some(_),
+            SpecialType::Def(Special {
+                body: Body::Struct(BodyStruct { fields: SpecialFields::Named(named), .. }),
+                ..
+            }),
+        ) => named.named.iter().flat_map(reference_fields).collect(),
+        _ => vec![copy_field(field)],
+    }
+}
+
+/// Copies a field, replacing the definitions in its type by their name
+fn copy_field(field: &SpecialField) -> SpecialField {
+    SpecialField {
+        // `#>[...]` only applies to definitions, which are not copied
+        attrs: field
+            .attrs
+            .iter()
+            .filter_map(|attr| match attr {
+                FieldAttribute::Field(attr) => Some(FieldAttribute::Field(attr.clone())),
+                FieldAttribute::Nested(_) => None,
+            })
+            .collect(),
+        vis: field.vis.clone(),
+        mutability: syn::FieldMutability::None,
+        ident: field.ident.clone(),
+        rename: field.rename.clone(),
+        question_token: field.question_token,
+        colon_token: field.colon_token,
+        ty: SpecialType::Type(field.ty.reference(&field.fish)),
+        fish: None,
+        default: field.default.clone(),
+        spread: None,
+    }
+}
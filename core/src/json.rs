@@ -0,0 +1,311 @@
+//! Nested types inferred from a JSON sample, with `nest_from_json!` and `nest_from_json_file!`.
+//!
+//! The sample is turned into the same syntax `nest!` accepts, with the keys as string literal
+//! field names, so the types are named exactly like handwritten ones. Objects become structs
+//! named after their key, arrays become `Vec`, and `null` or keys missing from some elements of
+//! an array become optional fields. Different objects under the same key are named after their
+//! parent as well: `AData` and `BData`.
+
+use crate::attributes::{CompositeAttribute, ParseAttribute};
+use crate::case::to_pascal_case;
+use crate::special_data::{Body, BodyStruct, Special, SpecialFields};
+use proc_macro2::{Span, TokenStream};
+use proc_macro_error::{abort, emit_error};
+use quote::quote;
+use serde_json::Value;
+use syn::parse::{Parse, ParseStream, Parser};
+use syn::{Ident, LitStr, Token, Visibility};
+
+/// `#[attrs]* vis Root, "source"`
+pub(crate) struct JsonInput {
+    pub attrs: Vec<CompositeAttribute>,
+    pub vis: Visibility,
+    pub ident: Ident,
+    /// The JSON sample, or the path to it
+    pub source: LitStr,
+}
+
+impl Parse for JsonInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(CompositeAttribute::parse_outer)?;
+        let vis = input.parse()?;
+        let ident = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let source = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+
+        Ok(JsonInput { attrs, vis, ident, source })
+    }
+}
+
+/// The inferred type of a JSON value
+#[derive(Clone)]
+struct Shape {
+    kind: Kind,
+    /// `null`, or a key that is missing from some objects
+    optional: bool,
+}
+
+#[derive(Clone)]
+enum Kind {
+    /// Only `null` or empty arrays were seen
+    Unknown,
+    Bool,
+    /// `true` if a value doesn't fit in `i64`
+    Integer(bool),
+    Float,
+    String,
+    Array(Box<Shape>),
+    Object(Vec<(String, Shape)>),
+}
+
+/// Builds the tree of the JSON sample `json`, which was written at `span`
+pub(crate) fn special(input: JsonInput, json: &str, span: Span) -> Special {
+    let value: Value = serde_json::from_str(json).unwrap_or_else(|err| {
+        abort!(span, "invalid JSON: {}", err)
+    });
+
+    let Value::Object(_) = value else {
+        abort!(
+            span, "expected a JSON object";
+            note = "the outermost value of the sample becomes the struct `{}`", input.ident
+        )
+    };
+
+    let shape = infer(&value, "", span);
+    let ident = &input.ident;
+    let definition = Names::resolve(|names| ty(&shape.kind, &ident.to_string(), span, names));
+
+    tree(input, definition, span)
+}
+
+/// Parses the definition of the outermost type, with the attributes of the invocation
+pub(crate) fn tree(input: JsonInput, definition: TokenStream, span: Span) -> Special {
+    let mut special: Special = syn::parse2(definition)
+        .unwrap_or_else(|err| abort!(span, "cannot define these types: {}", err));
+
+    // every generated definition and field is as visible as the outermost type
+    let vis = &input.vis;
+    special.visit(&mut |special| {
+        special.vis = vis.clone();
+
+        let fields = match &mut special.body {
+            Body::Struct(BodyStruct { fields, .. }) => vec![fields],
+            // the visibility of definitions in tuple variants comes from their field
+            Body::Enum(body) => body
+                .variants
+                .iter_mut()
+                .map(|variant| &mut variant.fields)
+                .filter(|fields| matches!(fields, SpecialFields::Unnamed(_)))
+                .collect(),
+        };
+        for fields in fields {
+            match fields {
+                SpecialFields::Named(named) => named.named.iter_mut().for_each(|field| field.vis = vis.clone()),
+                SpecialFields::Unnamed(unnamed) => unnamed.unnamed.iter_mut().for_each(|field| field.vis = vis.clone()),
+                SpecialFields::Unit => {}
+            }
+        }
+    });
+
+    // keys are renamed with serde, and definitions of the same shape are merged into one
+    let directives = CompositeAttribute::parse_outer
+        .parse2(quote!(#[nest::serde]* #[nest::dedup]*))
+        .expect("valid attributes");
+    special.attrs = [directives, input.attrs].concat();
+    special
+}
+
+/// Infers the shape of a value, `path` locates it in the sample for diagnostics
+fn infer(value: &Value, path: &str, span: Span) -> Shape {
+    let kind = match value {
+        Value::Null => return Shape { kind: Kind::Unknown, optional: true },
+        Value::Bool(_) => Kind::Bool,
+        Value::Number(number) if number.is_f64() => Kind::Float,
+        Value::Number(number) => Kind::Integer(!number.is_i64()),
+        Value::String(_) => Kind::String,
+        Value::Array(values) => {
+            let element = values.iter().fold(
+                Shape { kind: Kind::Unknown, optional: false },
+                |element, value| merge(element, infer(value, path, span), path, span),
+            );
+            Kind::Array(Box::new(element))
+        }
+        Value::Object(object) => Kind::Object(
+            object
+                .iter()
+                .map(|(key, value)| (key.clone(), infer(value, &format!("{path}.{key}"), span)))
+                .collect(),
+        ),
+    };
+
+    Shape { kind, optional: false }
+}
+
+/// Combines the shapes of two values in the same place, such as elements of an array
+fn merge(a: Shape, b: Shape, path: &str, span: Span) -> Shape {
+    let optional = a.optional || b.optional;
+
+    let kind = match (a.kind, b.kind) {
+        (Kind::Unknown, kind) | (kind, Kind::Unknown) => kind,
+        (Kind::Bool, Kind::Bool) => Kind::Bool,
+        (Kind::Integer(a), Kind::Integer(b)) => Kind::Integer(a || b),
+        (Kind::Integer(_) | Kind::Float, Kind::Integer(_) | Kind::Float) => Kind::Float,
+        (Kind::String, Kind::String) => Kind::String,
+        (Kind::Array(a), Kind::Array(b)) => Kind::Array(Box::new(merge(*a, *b, path, span))),
+        (Kind::Object(mut a), Kind::Object(b)) => {
+            // keys that are missing from one of the objects are optional
+            for (key, shape) in a.iter_mut() {
+                if !b.iter().any(|(other, _)| other == key) {
+                    shape.optional = true;
+                }
+            }
+            for (key, shape) in b {
+                match a.iter_mut().find(|(other, _)| *other == key) {
+                    Some((_, existing)) => {
+                        let path = format!("{path}.{key}");
+                        *existing = merge(existing.clone(), shape, &path, span);
+                    }
+                    None => a.push((key, Shape { optional: true, ..shape })),
+                }
+            }
+            Kind::Object(a)
+        }
+        _ => {
+            emit_error!(
+                span, "conflicting types for `{}` in the JSON sample", path.trim_start_matches('.');
+                note = "every element of an array must have the same type"
+            );
+            Kind::Unknown
+        }
+    };
+
+    Shape { kind, optional }
+}
+
+/// `"key"?: T`, where objects are defined inline as structs named after the key
+fn field(key: &str, shape: &Shape, parent: &str, span: Span, names: &mut Names) -> TokenStream {
+    let name = LitStr::new(key, span);
+    let question = shape.optional.then(|| quote!(?));
+    let ty = ty(&shape.kind, &names.name(key, parent), span, names);
+
+    quote!(#name #question : #ty)
+}
+
+fn ty(kind: &Kind, name: &str, span: Span, names: &mut Names) -> TokenStream {
+    match kind {
+        Kind::Unknown => quote!(()),
+        Kind::Bool => quote!(bool),
+        Kind::Integer(false) => quote!(i64),
+        Kind::Integer(true) => quote!(u64),
+        Kind::Float => quote!(f64),
+        Kind::String => quote!(String),
+        Kind::Array(element) => {
+            let inner = ty(&element.kind, &format!("{name}Item"), span, names);
+            match element.optional {
+                true => quote!(Vec<Option<#inner>>),
+                false => quote!(Vec<#inner>),
+            }
+        }
+        Kind::Object(fields) => {
+            let ident = Ident::new(name, span);
+            let fields: Vec<TokenStream> = fields
+                .iter()
+                .map(|(key, shape)| field(key, shape, name, span, names))
+                .collect();
+            let definition = quote!(struct #ident { #(#fields),* });
+
+            names.record(name, &definition);
+            definition
+        }
+    }
+}
+
+/// The name of the struct defined for `key`: `user_profile` -> `UserProfile`. Keys that don't
+/// start with a letter are prefixed with the name of the parent: `2fa` -> `User2fa`, and
+/// keywords get a trailing underscore: `self` -> `Self_`
+pub(crate) fn type_name(key: &str, parent: &str) -> String {
+    let mut name = to_pascal_case(key);
+
+    if !name.starts_with(|ch: char| ch.is_alphabetic()) {
+        name.insert_str(0, parent);
+    }
+    if syn::parse_str::<Ident>(&name).is_err() {
+        name.push('_');
+    }
+    name
+}
+
+/// The names of the definitions generated for a document.
+///
+/// Definitions are named after their key, which different definitions may share. The document
+/// is generated a second time when that happens, with the shared names prefixed with the name
+/// of the parent definition.
+#[derive(Default)]
+pub(crate) struct Names {
+    /// Every name with the definitions that were given it
+    definitions: Vec<(String, Vec<String>)>,
+    /// The names given to different definitions, qualified in the second generation
+    qualified: Vec<String>,
+}
+
+impl Names {
+    /// Runs `generate`, and runs it again with qualified names if names were shared
+    pub fn resolve<T>(mut generate: impl FnMut(&mut Names) -> T) -> T {
+        let mut names = Names::default();
+        let output = generate(&mut names);
+
+        let qualified: Vec<String> = names
+            .definitions
+            .into_iter()
+            .filter(|(_, definitions)| definitions.len() > 1)
+            .map(|(name, _)| name)
+            .collect();
+        if qualified.is_empty() {
+            return output;
+        }
+
+        generate(&mut Names {
+            qualified,
+            ..Names::default()
+        })
+    }
+
+    /// The name of the definition for `key`, nested in the definition named `parent`
+    pub fn name(&self, key: &str, parent: &str) -> String {
+        self.qualify(type_name(key, parent), parent)
+    }
+
+    /// Prefixes `name` with the name of the parent if different definitions share it
+    pub fn qualify(&self, name: String, parent: &str) -> String {
+        match self.qualified.contains(&name) {
+            true => format!("{parent}{name}"),
+            false => name,
+        }
+    }
+
+    /// Records that `definition` is named `name`
+    pub fn record(&mut self, name: &str, definition: &TokenStream) {
+        let definition = definition.to_string();
+
+        match self.definitions.iter_mut().find(|(existing, _)| existing == name) {
+            Some((_, definitions)) if !definitions.contains(&definition) => definitions.push(definition),
+            Some(_) => {}
+            None => self.definitions.push((name.to_string(), vec![definition])),
+        }
+    }
+}
+
+/// Reads a file relative to the crate that invokes the macro
+pub(crate) fn read(path: &LitStr) -> (String, String) {
+    let root = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let full = std::path::Path::new(&root).join(path.value());
+
+    match std::fs::read_to_string(&full) {
+        Ok(contents) => (full.display().to_string(), contents),
+        Err(err) => abort!(
+            path.span(), "cannot read `{}`: {}", full.display(), err;
+            note = "the path is relative to the directory of `Cargo.toml`"
+        ),
+    }
+}
@@ -0,0 +1,196 @@
+//! The parser and expansion behind `nestify::nest!`, for procedural macros that accept nestify
+//! syntax.
+//!
+//! Input is parsed into a [`Special`] tree, which can be inspected and changed before it is
+//! expanded into the definitions with [`expand`]:
+//!
+//! ```
+//! let mut tree = nestify_core::parse(quote::quote!(
+//!     struct User {
+//!         address: struct Address {
+//!             city: String,
+//!         },
+//!     }
+//! ))
+//! .unwrap();
+//!
+//! // every definition in the tree is public
+//! tree.visit(&mut |special| special.vis = syn::parse_quote!(pub));
+//!
+//! let expansion = nestify_core::expand(tree).to_string();
+//! assert!(expansion.contains("pub struct Address"));
+//! ```
+//!
+//! The types of the tree are `#[non_exhaustive]`: they are built by parsing, and their fields can
+//! be read and changed.
+//!
+//! Errors in the input are reported with `proc-macro-error`, so the procedural macro that calls
+//! [`expand`] has to be annotated with `#[proc_macro_error]`.
+
+use proc_macro2::TokenStream;
+use proc_macro_error::abort_call_site;
+
+#[cfg(test)]
+mod tests;
+pub(crate) mod attributes;
+pub(crate) mod case;
+pub(crate) mod directives;
+pub(crate) mod discriminant;
+#[cfg(feature = "dump")]
+pub(crate) mod dump;
+pub(crate) mod fish;
+pub(crate) mod generate;
+pub(crate) mod inherit;
+#[cfg(feature = "json")]
+pub(crate) mod json;
+#[cfg(feature = "json")]
+pub(crate) mod openapi;
+pub(crate) mod partial;
+pub(crate) mod registry;
+#[cfg(feature = "json")]
+pub(crate) mod schema;
+pub(crate) mod serde_attrs;
+pub(crate) mod special_data;
+pub(crate) mod twin;
+pub(crate) mod ty;
+pub(crate) mod unpack_context;
+pub(crate) mod versions;
+pub(crate) mod views;
+
+/// Provides functionality for unpacking special data structures.
+///
+/// This module defines traits and implementations for recursively unpacking
+/// data structures annotated with custom attributes, facilitating a form of
+/// metaprogramming within Rust macros.
+pub(crate) mod unpack;
+mod attribute_removal;
+
+pub use attributes::{Attribute, AttributeModifier, CompositeAttribute, FieldAttribute, NestedAttribute};
+pub use discriminant::Discriminant;
+pub use fish::FishHook;
+pub use special_data::{
+    Body, BodyEnum, BodyStruct, FieldsNamed, FieldsUnnamed, Special, SpecialField, SpecialFields, SpecialVariant,
+};
+pub use ty::SpecialType;
+
+use unpack::Unpack;
+use unpack_context::UnpackContext;
+
+/// Parses the input of `nest!` into a tree
+pub fn parse(input: TokenStream) -> syn::Result<Special> {
+    syn::parse2(input)
+}
+
+/// Expands a tree into its definitions
+pub fn expand(def: Special) -> TokenStream {
+    expand_with([def])
+}
+
+/// Expands trees into their definitions, together.
+///
+/// Clashing names are reported across the trees and `#[nest::dedup]` merges definitions from all
+/// of them.
+pub fn expand_with(defs: impl IntoIterator<Item = Special>) -> TokenStream {
+    let context = UnpackContext::default();
+    defs.into_iter().map(|def| expand_tree(def, context.clone())).collect()
+}
+
+/// Expands a tree, registering its definitions in `context`
+fn expand_tree(mut def: Special, context: UnpackContext) -> TokenStream {
+    #[cfg(feature = "dump")]
+    let dump = dump::take(&mut def).then(|| (def.vis.clone(), def.ident.clone(), def.generics.clone()));
+    inherit::resolve(&mut def);
+
+    #[cfg_attr(not(feature = "dump"), allow(unused_variables))]
+    let (expansion, versions) = match versions::expand(&mut def) {
+        Some(versioned) => versioned,
+        None => (def.unpack(context, Vec::default(), None, false), vec![]),
+    };
+
+    #[cfg(feature = "dump")]
+    if let Some((vis, root, generics)) = dump {
+        let dump = dump::dump(&vis, &root, &generics, &versions, &expansion);
+        return quote::quote!(#expansion #dump);
+    }
+    expansion
+}
+
+/// The expansion of `nest!`
+pub fn nest(input: TokenStream) -> TokenStream {
+    if input.is_empty() {
+        abort_call_site!(
+            "Nest! macro expansion failed: The input is empty.";
+            note = "The nest! macro expects a non-empty `struct` or `enum` definition to function properly.";
+            help = "Please ensure that you are using the nest! macro with a valid `struct` or `enum`.\
+            Refer to documentation for information on how to use this macro and more examples";
+        );
+    }
+
+    match parse(input) {
+        Ok(def) => expand(def),
+        Err(err) => err.to_compile_error(),
+    }
+}
+
+/// The expansion of `nest_from_json!`
+#[cfg(feature = "json")]
+pub fn nest_from_json(input: TokenStream) -> TokenStream {
+    let input: json::JsonInput = match syn::parse2(input) {
+        Ok(input) => input,
+        Err(err) => return err.to_compile_error(),
+    };
+    let (json, span) = (input.source.value(), input.source.span());
+
+    expand(json::special(input, &json, span))
+}
+
+/// The expansion of `nest_from_json_file!`
+#[cfg(feature = "json")]
+pub fn nest_from_json_file(input: TokenStream) -> TokenStream {
+    let input: json::JsonInput = match syn::parse2(input) {
+        Ok(input) => input,
+        Err(err) => return err.to_compile_error(),
+    };
+    let (path, span) = (input.source.clone(), input.source.span());
+
+    from_file(&path, |json| expand(json::special(input, json, span)))
+}
+
+/// The expansion of `nest_from_schema!`
+#[cfg(feature = "json")]
+pub fn nest_from_schema(input: TokenStream) -> TokenStream {
+    let input: json::JsonInput = match syn::parse2(input) {
+        Ok(input) => input,
+        Err(err) => return err.to_compile_error(),
+    };
+    let (path, span) = (input.source.clone(), input.source.span());
+
+    from_file(&path, |json| expand(schema::special(input, json, span)))
+}
+
+/// The expansion of `nest_openapi!`
+#[cfg(feature = "json")]
+pub fn nest_openapi(input: TokenStream) -> TokenStream {
+    let input: openapi::OpenApiInput = match syn::parse2(input) {
+        Ok(input) => input,
+        Err(err) => return err.to_compile_error(),
+    };
+    let path = input.path.clone();
+
+    // the schemas share definitions that are referenced by more than one of them
+    from_file(&path, |document| expand_with(openapi::specials(input, document)))
+}
+
+/// Expands the types defined by the contents of the file at `path`, relative to the crate
+/// that invokes the macro
+#[cfg(feature = "json")]
+fn from_file(path: &syn::LitStr, types: impl FnOnce(&str) -> TokenStream) -> TokenStream {
+    let (path, contents) = json::read(path);
+    let types = types(&contents);
+
+    // rebuild when the file changes
+    quote::quote!(
+        #types
+        const _: &str = include_str!(#path);
+    )
+}
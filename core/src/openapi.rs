@@ -0,0 +1,136 @@
+//! Nested types for the schemas of an OpenAPI document, with `nest_openapi!`.
+//!
+//! Every selected schema of `components/schemas` is defined like a [JSON Schema](crate::schema),
+//! with anonymous objects in it defined inline and named after their property. Different objects
+//! under the same property of any of the schemas are also named after their parent. References to
+//! other selected schemas use their name, references to the rest are defined where they are used.
+//! Like in JSON Schema, `discriminator` is ignored and `oneOf` is untagged.
+
+use crate::attributes::{CompositeAttribute, ParseAttribute};
+use crate::json::{tree, JsonInput, Names};
+use crate::schema::Schema;
+use crate::special_data::Special;
+use proc_macro2::TokenStream;
+use proc_macro_error::{abort, emit_error, Diagnostic};
+use serde_json::Value;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{bracketed, Ident, LitStr, Token, Visibility};
+
+/// `#[attrs]* vis "path", components = [User, Order]`
+pub(crate) struct OpenApiInput {
+    pub attrs: Vec<CompositeAttribute>,
+    pub vis: Visibility,
+    pub path: LitStr,
+    pub components: Vec<Ident>,
+}
+
+impl Parse for OpenApiInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(CompositeAttribute::parse_outer)?;
+        let vis = input.parse()?;
+        let path = input.parse()?;
+        input.parse::<Token![,]>()?;
+
+        let key: Ident = input.parse()?;
+        if key != "components" {
+            return Err(syn::Error::new(key.span(), "expected `components = [...]`"));
+        }
+        input.parse::<Token![=]>()?;
+
+        let content;
+        bracketed!(content in input);
+        let components = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?;
+        input.parse::<Option<Token![,]>>()?;
+
+        Ok(OpenApiInput {
+            attrs,
+            vis,
+            path,
+            components: components.into_iter().collect(),
+        })
+    }
+}
+
+/// Builds a tree for every selected schema of the document
+pub(crate) fn specials(input: OpenApiInput, document: &str) -> Vec<Special> {
+    let span = input.path.span();
+    let yaml = input.path.value().ends_with(".yaml") || input.path.value().ends_with(".yml");
+
+    let root: Value = match yaml {
+        #[cfg(feature = "yaml")]
+        true => serde_norway::from_str(document).unwrap_or_else(|err| abort!(span, "invalid YAML: {}", err)),
+        #[cfg(not(feature = "yaml"))]
+        true => abort!(
+            span, "YAML documents require the `yaml` feature of nestify";
+            help = "enable it with `nestify = { version = \"...\", features = [\"yaml\"] }`, or use a JSON document"
+        ),
+        false => serde_json::from_str(document).unwrap_or_else(|err| abort!(span, "invalid JSON: {}", err)),
+    };
+
+    let pointer = |component: &Ident| format!("/components/schemas/{component}");
+    let shared: Vec<(String, String)> = input
+        .components
+        .iter()
+        .map(|component| (pointer(component), component.to_string()))
+        .collect();
+
+    let schemas: Vec<(&Ident, &Value)> = input
+        .components
+        .iter()
+        .filter_map(|component| match root.pointer(&pointer(component)) {
+            Some(schema) => Some((component, schema)),
+            None => {
+                emit_error!(
+                    component, "cannot find `components/schemas/{}` in `{}`", component, input.path.value()
+                );
+                None
+            }
+        })
+        .collect();
+
+    // the names of definitions nested in different schemas are qualified together
+    let (definitions, errors) = Names::resolve(|names| {
+        let mut errors = vec![];
+        let definitions: Vec<Option<TokenStream>> = schemas
+            .iter()
+            .map(|(component, schema)| {
+                let mut context = Schema {
+                    root: &root,
+                    span: component.span(),
+                    refs: vec![(pointer(component), component.to_string())],
+                    shared: shared.clone(),
+                    names: &mut *names,
+                    errors: vec![],
+                };
+                let definition = context.definition(schema, &component.to_string());
+                errors.append(&mut context.errors);
+                definition
+            })
+            .collect();
+        (definitions, errors)
+    });
+    errors.into_iter().for_each(Diagnostic::emit);
+
+    schemas
+        .into_iter()
+        .zip(definitions)
+        .filter_map(|((component, _), definition)| {
+            let Some(definition) = definition else {
+                emit_error!(
+                    component, "`{}` is not an object, an enum or `oneOf`", component;
+                    note = "only schemas that define a struct or an enum can be selected"
+                );
+                return None;
+            };
+
+            let input = JsonInput {
+                attrs: input.attrs.clone(),
+                vis: input.vis.clone(),
+                ident: component.clone(),
+                source: input.path.clone(),
+            };
+            Some(tree(input, definition, component.span()))
+        })
+        .collect()
+}
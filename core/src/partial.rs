@@ -0,0 +1,99 @@
+//! Partial twins of structs, defined with `#[nest::partial(Name)]`.
+//!
+//! The twin of a struct has the same fields, but every field is optional. Nested structs
+//! with named fields get twins of their own, named with the same suffix: the twin of `User`
+//! is `UserPatch`, and the twin of its `address: struct Address { .. }` is `AddressPatch`.
+
+use crate::attributes::CompositeAttribute;
+use crate::special_data::{Body, BodyStruct, Special, SpecialField, SpecialFields};
+use crate::ty::SpecialType;
+use crate::twin::{has_twin, twin_attrs, twin_field_attrs, twin_ident};
+use proc_macro_error::emit_error;
+use syn::ext::IdentExt;
+use syn::{parse_quote, FieldMutability, Generics, Ident, Visibility};
+
+/// The suffix of the twins from the name of the outermost twin: `UserPatch` -> `Patch`
+pub(crate) fn suffix(ident: &Ident, partial: &Ident) -> Option<String> {
+    let ident = ident.unraw().to_string();
+
+    match partial.unraw().to_string().strip_prefix(&ident) {
+        Some(suffix) if !suffix.is_empty() => Some(suffix.to_string()),
+        _ => {
+            emit_error!(
+                partial.span(),
+                "the name of the partial struct must start with `{}`, such as `{}Patch`", ident, ident;
+                note = "nested structs get partial twins named with the same suffix"
+            );
+            None
+        }
+    }
+}
+
+/// Builds the partial twin `ident` of a struct with the attributes `attrs` and named `fields`
+pub(crate) fn twin(
+    ident: &Ident,
+    vis: &Visibility,
+    generics: &Generics,
+    attrs: &[CompositeAttribute],
+    fields: &SpecialFields,
+    suffix: &str,
+) -> Special {
+    let SpecialFields::Named(named) = fields else {
+        unreachable!("partial twins are only built for structs with named fields")
+    };
+
+    Special {
+        attrs: twin_attrs(attrs),
+        vis: vis.clone(),
+        ident: ident.clone(),
+        generics: generics.clone(),
+        body: Body::Struct(BodyStruct::named(
+            named.named.iter().map(|field| twin_field(field, suffix)).collect(),
+        )),
+    }
+}
+
+fn twin_field(field: &SpecialField, suffix: &str) -> SpecialField {
+    let (attrs, ty, fish) = match &field.ty {
+        SpecialType::Def(special) if has_twin(field, special) => {
+            let Body::Struct(BodyStruct { fields, .. }) = &special.body else {
+                unreachable!("checked by `has_twin`")
+            };
+            let twin = twin(
+                &twin_ident(&special.ident, suffix),
+                &special.vis,
+                &special.generics,
+                &special.attrs,
+                fields,
+                suffix,
+            );
+
+            (twin_field_attrs(&field.attrs, true), SpecialType::Def(twin), field.fish.clone())
+        }
+        // everything else is replaced as a whole, the definitions in it are not defined again
+        ty => {
+            let ty = ty.reference(&field.fish);
+            let ty = match field.question_token {
+                Some(_) => parse_quote!(::core::option::Option<#ty>),
+                None => ty,
+            };
+
+            (twin_field_attrs(&field.attrs, false), SpecialType::Type(ty), None)
+        }
+    };
+
+    SpecialField {
+        attrs,
+        vis: field.vis.clone(),
+        mutability: FieldMutability::None,
+        ident: field.ident.clone(),
+        rename: field.rename.clone(),
+        // spread twins are embedded as they are, every other field becomes optional
+        question_token: field.spread.is_none().then(Default::default),
+        colon_token: field.colon_token,
+        ty,
+        fish,
+        default: None,
+        spread: field.spread,
+    }
+}
@@ -0,0 +1,74 @@
+use crate::directives::Directives;
+use proc_macro2::Span;
+use syn::Ident;
+
+/// How a definition stores its data
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DefinitionKind {
+    NamedStruct,
+    TupleStruct,
+    UnitStruct,
+    Enum,
+}
+
+/// A definition that has already been expanded in the current invocation
+pub(crate) struct Entry {
+    pub ident: Ident,
+    pub kind: DefinitionKind,
+    pub directives: Directives,
+    /// The expanded item, without the definitions nested inside it.
+    /// Two definitions are structurally identical when their keys match.
+    key: String,
+}
+
+/// Every definition expanded by a single `nest!` invocation.
+///
+/// Shared by all clones of an [`UnpackContext`](crate::unpack_context::UnpackContext)
+/// so that definitions in different branches of the tree can see each other.
+#[derive(Default)]
+pub(crate) struct Registry {
+    entries: Vec<Entry>,
+}
+
+/// The outcome of registering a definition
+pub(crate) enum Registration {
+    /// The name is not defined anywhere else yet
+    Fresh,
+    /// An identical definition already exists and will be used instead
+    Merged,
+    /// The name is already taken by the definition at the span
+    Conflict(Span),
+}
+
+impl Registry {
+    /// Records a definition. With `#[nest::dedup]` it is merged into an earlier definition
+    /// of the same name if both expand to the same item.
+    pub fn register(
+        &mut self,
+        ident: &Ident,
+        key: String,
+        kind: DefinitionKind,
+        directives: &Directives,
+    ) -> Registration {
+        let Some(existing) = self.get(ident) else {
+            self.entries.push(Entry {
+                ident: ident.clone(),
+                kind,
+                directives: directives.clone(),
+                key,
+            });
+            return Registration::Fresh;
+        };
+
+        if directives.dedup && existing.key == key {
+            Registration::Merged
+        } else {
+            Registration::Conflict(existing.ident.span())
+        }
+    }
+
+    /// Looks up an expanded definition by name
+    pub fn get(&self, ident: &Ident) -> Option<&Entry> {
+        self.entries.iter().find(|entry| entry.ident == *ident)
+    }
+}
@@ -0,0 +1,292 @@
+//! Nested types defined from a JSON Schema file, with `nest_from_schema!`.
+//!
+//! Like [JSON samples](crate::json), the schema is turned into the same syntax `nest!` accepts.
+//! Objects become structs, enums of strings become enums with string values, and `oneOf`
+//! becomes an untagged enum with a variant for every alternative. Definitions referenced with
+//! `$ref` are defined at every use and merged into one with `#[nest::dedup]`. Definitions are named like
+//! the ones of [JSON samples](crate::json::Names). Values of any type, such as schemas without a
+//! `type`, are reported instead of becoming `()`, which only deserializes from `null`.
+//!
+//! `discriminator` and `const` properties are ignored: a value of a `oneOf` becomes the first
+//! alternative it deserializes into, so alternatives that accept the same objects have to be
+//! listed from the most to the least specific.
+
+use crate::json::{tree, type_name, JsonInput, Names};
+use crate::special_data::Special;
+use proc_macro2::{Span, TokenStream};
+use proc_macro_error::{abort, Diagnostic, Level};
+use quote::quote;
+use serde_json::Value;
+use syn::{Ident, LitStr};
+
+/// Builds the tree of the schema `json`, read from the file at `span`
+pub(crate) fn special(input: JsonInput, json: &str, span: Span) -> Special {
+    let root: Value = serde_json::from_str(json).unwrap_or_else(|err| {
+        abort!(span, "invalid JSON Schema: {}", err)
+    });
+
+    let name = input.ident.to_string();
+    let (definition, errors) = Names::resolve(|names| {
+        let mut schema = Schema {
+            root: &root,
+            span,
+            refs: vec![(String::new(), name.clone())],
+            shared: vec![],
+            names,
+            errors: vec![],
+        };
+        (schema.definition(&root, &name), schema.errors)
+    });
+    errors.into_iter().for_each(Diagnostic::emit);
+
+    let Some(definition) = definition else {
+        abort!(
+            span, "expected a schema of an object, an enum or `oneOf`";
+            note = "the outermost schema becomes the type `{}`", input.ident
+        )
+    };
+
+    tree(input, definition, span)
+}
+
+pub(crate) struct Schema<'a> {
+    /// The document, which `$ref` points into
+    pub root: &'a Value,
+    pub span: Span,
+    /// The JSON pointers and names of the definitions being defined, to detect recursive types
+    pub refs: Vec<(String, String)>,
+    /// The JSON pointers and names of definitions that are defined on their own,
+    /// which are referred to by name
+    pub shared: Vec<(String, String)>,
+    pub names: &'a mut Names,
+    /// Reported once the names are resolved, since the schema may be generated twice
+    pub errors: Vec<Diagnostic>,
+}
+
+impl Schema<'_> {
+    /// The definition of a struct or enum named `name`, or `None` if the schema doesn't define one
+    pub fn definition(&mut self, schema: &Value, name: &str) -> Option<TokenStream> {
+        let ident = Ident::new(name, self.span);
+        let doc = description(schema, self.span);
+
+        let body = if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+            self.string_enum(&ident, values)
+        } else if let Some(alternatives) = one_of(schema) {
+            self.one_of(&ident, alternatives)
+        } else {
+            self.properties(schema, name)?
+        };
+
+        let definition = quote!(#doc #body);
+        self.names.record(name, &definition);
+        Some(definition)
+    }
+
+    /// The struct of an object schema named `name`
+    fn properties(&mut self, schema: &Value, name: &str) -> Option<TokenStream> {
+        let ident = Ident::new(name, self.span);
+        let properties = schema.get("properties").and_then(Value::as_object)?;
+        let required: Vec<&str> = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|required| required.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+
+        let fields = properties.iter().map(|(key, property)| {
+            let (ty, nullable) = self.ty(property, &self.names.name(key, name));
+            let key_lit = LitStr::new(key, self.span);
+            let question = (nullable || !required.contains(&key.as_str())).then(|| quote!(?));
+            let doc = description(property, self.span);
+
+            quote!(#doc #key_lit #question : #ty)
+        });
+        let fields: Vec<TokenStream> = fields.collect();
+
+        Some(quote!(struct #ident { #(#fields),* }))
+    }
+
+    /// The type of a value and whether it may be `null`
+    pub fn ty(&mut self, schema: &Value, name: &str) -> (TokenStream, bool) {
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            return self.reference(reference);
+        }
+
+        let types: Vec<&str> = match schema.get("type") {
+            Some(Value::String(ty)) => vec![ty],
+            Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+            _ => vec![],
+        };
+        let nullable = types.contains(&"null") || schema.get("nullable") == Some(&Value::Bool(true));
+
+        // `oneOf` with `null` as an alternative is an optional value
+        if let Some(alternatives) = one_of(schema) {
+            let values: Vec<&Value> = alternatives.iter().filter(|alternative| !is_null(alternative)).collect();
+            if let ([value], true) = (values.as_slice(), values.len() < alternatives.len()) {
+                let (ty, _) = self.ty(value, name);
+                return (ty, true);
+            }
+        }
+
+        if let Some(definition) = self.definition(schema, name) {
+            return (definition, nullable);
+        }
+
+        let ty = match types.iter().find(|ty| **ty != "null").copied() {
+            Some("string") => quote!(String),
+            Some("boolean") => quote!(bool),
+            Some("integer") => match schema.get("format").and_then(Value::as_str) {
+                Some("int32") => quote!(i32),
+                _ => quote!(i64),
+            },
+            Some("number") => match schema.get("format").and_then(Value::as_str) {
+                Some("float") => quote!(f32),
+                _ => quote!(f64),
+            },
+            Some("array") => {
+                let items = schema.get("items").unwrap_or(&Value::Null);
+                let (item, nullable) = self.ty(items, &format!("{name}Item"));
+                match nullable {
+                    true => quote!(Vec<Option<#item>>),
+                    false => quote!(Vec<#item>),
+                }
+            }
+            // objects without properties are maps
+            Some("object") => match schema.get("additionalProperties") {
+                Some(values @ Value::Object(_)) => {
+                    let (value, _) = self.ty(values, &format!("{name}Value"));
+                    quote!(std::collections::HashMap<String, #value>)
+                }
+                _ => self.untyped(name, "an object without `properties` or a schema of `additionalProperties`"),
+            },
+            // only `null`
+            None if !types.is_empty() => quote!(()),
+            None => self.untyped(name, "a schema without `type`"),
+            // anything else can't be represented without a dynamic type
+            Some(ty) => self.untyped(name, &format!("a schema of type `{ty}`")),
+        };
+
+        (ty, nullable)
+    }
+
+    /// Reports a value of `name` that can't be typed, since `()` only deserializes from `null`
+    fn untyped(&mut self, name: &str, schema: &str) -> TokenStream {
+        self.errors.push(
+            Diagnostic::spanned(self.span, Level::Error, format!("cannot define a type for `{name}`, from {schema}"))
+                .note("add a `type`, and `properties` or `additionalProperties` to objects; values of any type are not supported".to_string()),
+        );
+        quote!(())
+    }
+
+    /// Resolves `$ref` to a definition in the same document, named after the last segment
+    fn reference(&mut self, reference: &str) -> (TokenStream, bool) {
+        let Some((pointer, target)) = reference
+            .strip_prefix('#')
+            .and_then(|pointer| Some((pointer, self.root.pointer(pointer)?)))
+        else {
+            self.errors.push(
+                Diagnostic::spanned(self.span, Level::Error, format!("cannot resolve `$ref: {}`", reference))
+                    .note("only references within the same file are supported, such as `#/$defs/User`".to_string()),
+            );
+            return (quote!(()), false);
+        };
+
+        // recursive types refer to themselves by name
+        if let Some((_, name)) = self.refs.iter().find(|(defining, _)| defining == pointer) {
+            let ident = Ident::new(name, self.span);
+            return (quote!(Box<#ident>), false);
+        }
+        if let Some((_, name)) = self.shared.iter().find(|(shared, _)| shared == pointer) {
+            let ident = Ident::new(name, self.span);
+            return (quote!(#ident), false);
+        }
+
+        let segment = pointer.rsplit('/').next().unwrap_or_default();
+        let name = type_name(segment, "Ref");
+
+        self.refs.push((pointer.to_string(), name.clone()));
+        let ty = self.ty(target, &name);
+        self.refs.pop();
+        ty
+    }
+
+    /// `enum: ["active", "banned"]` becomes an enum with string values
+    fn string_enum(&mut self, ident: &Ident, values: &[Value]) -> TokenStream {
+        let variants = values.iter().filter_map(|value| {
+            let Value::String(value) = value else {
+                self.errors.push(Diagnostic::spanned(
+                    self.span,
+                    Level::Error,
+                    format!("only enums of strings are supported, found `{}`", value),
+                ));
+                return None;
+            };
+            let variant = Ident::new(&type_name(value, "Value"), self.span);
+            let value = LitStr::new(value, self.span);
+
+            Some(quote!(#variant = #value))
+        });
+        let variants: Vec<TokenStream> = variants.collect();
+
+        quote!(enum #ident { #(#variants),* })
+    }
+
+    /// `oneOf` becomes an untagged enum, with a variant for every alternative. Not tagged by
+    /// `discriminator`, since the tag would be taken out of the objects of the alternatives,
+    /// which are also used on their own.
+    fn one_of(&mut self, ident: &Ident, alternatives: &[Value]) -> TokenStream {
+        let variants = alternatives.iter().enumerate().map(|(index, alternative)| {
+            if is_null(alternative) {
+                return quote!(Null);
+            }
+
+            let name = variant_name(alternative, index);
+            let variant = Ident::new(&name, self.span);
+            let (ty, nullable) = self.ty(alternative, &self.names.qualify(name, &ident.to_string()));
+            match nullable {
+                true => quote!(#variant(Option<#ty>)),
+                false => quote!(#variant(#ty)),
+            }
+        });
+        let variants: Vec<TokenStream> = variants.collect();
+
+        quote!(
+            #[serde(untagged)]
+            enum #ident { #(#variants),* }
+        )
+    }
+}
+
+fn one_of(schema: &Value) -> Option<&Vec<Value>> {
+    schema
+        .get("oneOf")
+        .or_else(|| schema.get("anyOf"))
+        .and_then(Value::as_array)
+}
+
+fn is_null(schema: &Value) -> bool {
+    schema.get("type").and_then(Value::as_str) == Some("null")
+}
+
+/// The name of the variant for an alternative of `oneOf`, from its title, its reference,
+/// or its type
+fn variant_name(alternative: &Value, index: usize) -> String {
+    let name = alternative
+        .get("title")
+        .or_else(|| alternative.get("$ref"))
+        .or_else(|| alternative.get("type"))
+        .and_then(Value::as_str)
+        .and_then(|name| name.rsplit('/').next());
+
+    match name {
+        Some(name) => type_name(name, "Variant"),
+        None => format!("Variant{index}"),
+    }
+}
+
+/// `description` as a doc comment
+fn description(schema: &Value, span: Span) -> Option<TokenStream> {
+    let description = schema.get("description").and_then(Value::as_str)?;
+    let description = LitStr::new(&format!(" {description}"), span);
+
+    Some(quote!(#[doc = #description]))
+}
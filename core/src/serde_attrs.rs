@@ -0,0 +1,165 @@
+//! The `#[serde(...)]` attributes that change how a definition is (de)serialized, read from the
+//! attributes a definition is emitted with, so generators can describe the serialized form.
+
+use crate::attributes::Attribute;
+use syn::{LitStr, Meta};
+
+/// The serde attributes of a definition, field or variant
+#[derive(Default)]
+pub(crate) struct SerdeAttrs {
+    pub rename: Option<String>,
+    pub rename_all: Option<String>,
+    pub tag: Option<String>,
+    pub content: Option<String>,
+    pub untagged: bool,
+    pub flatten: bool,
+    /// `skip` or `skip_serializing`
+    pub skip: bool,
+    pub skip_serializing_if: bool,
+}
+
+impl SerdeAttrs {
+    pub fn parse(attrs: &[Attribute]) -> Self {
+        let mut serde = SerdeAttrs::default();
+
+        for attr in attrs {
+            let Meta::List(list) = &attr.meta else {
+                continue;
+            };
+            if !list.path.is_ident("serde") {
+                continue;
+            }
+
+            // serde reports malformed attributes itself
+            let _ = list.parse_nested_meta(|meta| {
+                let name = meta.path.get_ident().map(ToString::to_string).unwrap_or_default();
+                match name.as_str() {
+                    "rename" => serde.rename = serialized_name(&meta)?,
+                    "rename_all" => serde.rename_all = serialized_name(&meta)?,
+                    "tag" => serde.tag = Some(meta.value()?.parse::<LitStr>()?.value()),
+                    "content" => serde.content = Some(meta.value()?.parse::<LitStr>()?.value()),
+                    "untagged" => serde.untagged = true,
+                    "flatten" => serde.flatten = true,
+                    "skip" | "skip_serializing" => serde.skip = true,
+                    "skip_serializing_if" => {
+                        serde.skip_serializing_if = true;
+                        meta.value()?.parse::<LitStr>()?;
+                    }
+                    // skip the values of everything else
+                    _ if meta.input.peek(syn::Token![=]) => {
+                        meta.value()?.parse::<syn::Expr>()?;
+                    }
+                    _ if meta.input.peek(syn::token::Paren) => {
+                        meta.parse_nested_meta(|_| Ok(()))?;
+                    }
+                    _ => {}
+                }
+                Ok(())
+            });
+        }
+
+        serde
+    }
+
+    /// The serialized name of a field named `name`, in a definition with `rename_all`
+    pub fn field_name(&self, name: &str, rename_all: Option<&str>) -> String {
+        match (&self.rename, rename_all) {
+            (Some(rename), _) => rename.clone(),
+            (None, Some(case)) => rename_field(name, case),
+            (None, None) => name.to_string(),
+        }
+    }
+
+    /// The serialized name of a variant named `name`, in an enum with `rename_all`
+    pub fn variant_name(&self, name: &str, rename_all: Option<&str>) -> String {
+        match (&self.rename, rename_all) {
+            (Some(rename), _) => rename.clone(),
+            (None, Some(case)) => rename_variant(name, case),
+            (None, None) => name.to_string(),
+        }
+    }
+}
+
+/// `rename = "name"`, or the name in `rename(serialize = "name")`
+fn serialized_name(meta: &syn::meta::ParseNestedMeta) -> syn::Result<Option<String>> {
+    if meta.input.peek(syn::Token![=]) {
+        return Ok(Some(meta.value()?.parse::<LitStr>()?.value()));
+    }
+
+    let mut name = None;
+    meta.parse_nested_meta(|nested| {
+        let value = nested.value()?.parse::<LitStr>()?.value();
+        if nested.path.is_ident("serialize") {
+            name = Some(value);
+        }
+        Ok(())
+    })?;
+    Ok(name)
+}
+
+/// Converts a `snake_case` field name to a case of `rename_all`, like serde does
+pub(crate) fn rename_field(name: &str, case: &str) -> String {
+    let name = name.trim_start_matches("r#");
+
+    match case {
+        "UPPERCASE" | "SCREAMING_SNAKE_CASE" => name.to_ascii_uppercase(),
+        "PascalCase" => {
+            let mut pascal = String::new();
+            let mut capitalize = true;
+            for ch in name.chars() {
+                if ch == '_' {
+                    capitalize = true;
+                } else if capitalize {
+                    pascal.push(ch.to_ascii_uppercase());
+                    capitalize = false;
+                } else {
+                    pascal.push(ch);
+                }
+            }
+            pascal
+        }
+        "camelCase" => {
+            let pascal = rename_field(name, "PascalCase");
+            lower_first(&pascal)
+        }
+        "kebab-case" => name.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => name.to_ascii_uppercase().replace('_', "-"),
+        // `lowercase` and `snake_case` leave field names as they are
+        _ => name.to_string(),
+    }
+}
+
+/// Converts a `PascalCase` variant name to a case of `rename_all`, like serde does.
+/// Every uppercase letter starts a word, so `HTTPError` is `h_t_t_p_error` in `snake_case`.
+pub(crate) fn rename_variant(name: &str, case: &str) -> String {
+    let snake = || {
+        let mut snake = String::new();
+        for (idx, ch) in name.char_indices() {
+            if idx > 0 && ch.is_uppercase() {
+                snake.push('_');
+            }
+            snake.push(ch.to_ascii_lowercase());
+        }
+        snake
+    };
+
+    match case {
+        "lowercase" => name.to_ascii_lowercase(),
+        "UPPERCASE" => name.to_ascii_uppercase(),
+        "camelCase" => lower_first(name),
+        "snake_case" => snake(),
+        "SCREAMING_SNAKE_CASE" => snake().to_ascii_uppercase(),
+        "kebab-case" => snake().replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => snake().to_ascii_uppercase().replace('_', "-"),
+        // `PascalCase` leaves variant names as they are
+        _ => name.to_string(),
+    }
+}
+
+fn lower_first(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_lowercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
@@ -1,13 +1,14 @@
 use crate::attributes::{CompositeAttribute, FieldAttribute, ParseAttribute};
+use crate::case::field_ident;
 use crate::discriminant::Discriminant;
 use crate::fish::FishHook;
-use crate::ty::SpecialType;
-use proc_macro_error::abort;
+use crate::ty::{augmented, SpecialType};
 use syn::ext::IdentExt;
 use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
 use syn::{
-    braced, parenthesized, token, FieldMutability, Generics, Ident, Token, Visibility, WhereClause,
+    braced, parenthesized, token, Expr, FieldMutability, Generics, Ident, LitStr, Token,
+    Visibility, WhereClause,
 };
 
 // some comments are based on the `syn` crate documentation 
@@ -15,6 +16,8 @@ use syn::{
 /// The base type definition.
 /// It allows recursive definition expansions, therefore,
 /// it is *Special*
+#[derive(Clone)]
+#[non_exhaustive]
 pub struct Special {
     pub attrs: Vec<CompositeAttribute>, // used to be RecAttribute
     pub vis: Visibility,
@@ -24,6 +27,8 @@ pub struct Special {
 }
 
 /// The body of a definition; Contains the data for the item
+#[derive(Clone)]
+#[non_exhaustive]
 pub enum Body {
     Struct(BodyStruct),
     Enum(BodyEnum),
@@ -31,24 +36,91 @@ pub enum Body {
 }
 
 /// Structure Body aka Data in syn
+#[derive(Clone)]
+#[non_exhaustive]
 pub struct BodyStruct {
     struct_token: Token![struct],
+    /// Inheritance `struct B: A { .. }`, the struct starts with the named fields of `A`
+    pub inherits: Option<(Token![:], Ident)>,
     pub fields: SpecialFields,
     semi_token: Option<Token![;]>,
 }
 
 /// Enumeration Body aka Data in syn
+#[derive(Clone)]
+#[non_exhaustive]
 pub struct BodyEnum {
     enum_token: Token![enum],
     brace_token: token::Brace,
     pub variants: Punctuated<SpecialVariant, Token![,]>,
 }
 
+impl BodyStruct {
+    /// Creates the body of a struct with named fields for generated definitions
+    pub fn named(named: Punctuated<SpecialField, Token![,]>) -> Self {
+        BodyStruct {
+            struct_token: Default::default(),
+            inherits: None,
+            fields: SpecialFields::Named(FieldsNamed {
+                brace_token: Default::default(),
+                named,
+            }),
+            semi_token: None,
+        }
+    }
+}
+
+impl Special {
+    /// Calls `f` on every definition in the tree, parents before the definitions nested in them
+    pub fn visit(&mut self, f: &mut impl FnMut(&mut Special)) {
+        f(self);
+
+        let fields = match &mut self.body {
+            Body::Struct(BodyStruct { fields, .. }) => vec![fields],
+            Body::Enum(body) => body.variants.iter_mut().map(|variant| &mut variant.fields).collect(),
+        };
+
+        for fields in fields {
+            let fields = match fields {
+                SpecialFields::Named(named) => named.named.iter_mut(),
+                SpecialFields::Unnamed(unnamed) => unnamed.unnamed.iter_mut(),
+                SpecialFields::Unit => continue,
+            };
+
+            for field in fields {
+                visit_type(&mut field.ty, f);
+            }
+        }
+    }
+}
+
+fn visit_type(ty: &mut SpecialType, f: &mut impl FnMut(&mut Special)) {
+    match ty {
+        SpecialType::Def(special) => special.visit(f),
+        SpecialType::Augmented(augmented::Type::Path(path)) => {
+            for segment in path.path.segments.iter_mut() {
+                let augmented::PathArguments::AngleBracketed(arguments) = &mut segment.arguments else {
+                    continue;
+                };
+
+                for argument in arguments.args.iter_mut() {
+                    if let augmented::GenericArgument::Type(ty, _) = argument {
+                        visit_type(ty, f);
+                    }
+                }
+            }
+        }
+        SpecialType::Type(_) => {}
+    }
+}
+
 // struct BodyUnion {
 //     union_token: Token![union],
 // }
 
 /// An enum variant
+#[derive(Clone)]
+#[non_exhaustive]
 pub struct SpecialVariant {
     /// Attributes belonging to variant:
     /// ```txt
@@ -64,11 +136,13 @@ pub struct SpecialVariant {
     /// Content stored in the variant.
     pub fields: SpecialFields,
 
-    /// Explicit discriminant: `Variant = 1`
+    /// Explicit discriminant: `Variant = 1`, or string value: `Variant = "variant"`
     pub discriminant: Option<Discriminant>,
 }
 
 /// Data stored in an enum variant or structure
+#[derive(Clone)]
+#[non_exhaustive]
 pub enum SpecialFields {
     /// Named fields of a struct or struct variant such as
     /// `Point {
@@ -90,12 +164,16 @@ pub enum SpecialFields {
 /// x: f64,
 /// y: f64
 /// }`
+#[derive(Clone)]
+#[non_exhaustive]
 pub struct FieldsNamed {
     pub brace_token: token::Brace,
     pub named: Punctuated<SpecialField, Token![,]>,
 }
 
 /// Unnamed fields of a tuple struct or tuple variant such as `Some(T)`.
+#[derive(Clone)]
+#[non_exhaustive]
 pub struct FieldsUnnamed {
     pub paren_token: token::Paren,
     pub unnamed: Punctuated<SpecialField, Token![,]>,
@@ -104,15 +182,25 @@ pub struct FieldsUnnamed {
 // note: refactor to a new file eventually
 
 /// A field of a struct or enum variant.
+#[derive(Clone)]
+#[non_exhaustive]
 pub struct SpecialField {
     pub attrs: Vec<FieldAttribute>,
     pub vis: Visibility,
     pub mutability: FieldMutability,
     /// Name of the field if any
     pub ident: Option<Ident>,
+    /// The original name if the field was named with a string literal: `"user-id": T`
+    pub rename: Option<LitStr>,
+    /// Optional shorthand `field?: T`, wraps the type in `Option`
+    pub question_token: Option<Token![?]>,
     pub colon_token: Option<Token![:]>,
     pub ty: SpecialType,
     pub fish: Option<FishHook>,
+    /// Default value of the field: `field: T = value`
+    pub default: Option<(Token![=], Expr)>,
+    /// Spread syntax `..struct Name { .. }`, embeds the fields of the struct in the parent
+    pub spread: Option<Token![..]>,
 }
 
 impl Parse for Special {
@@ -125,7 +213,20 @@ impl Parse for Special {
             let struct_token = input.parse::<Token![struct]>()?;
             let ident = input.parse::<Ident>()?;
             let generics = input.parse::<Generics>()?;
+            let inherits = if input.peek(Token![:]) {
+                Some((input.parse()?, input.parse()?))
+            } else {
+                None
+            };
             let (where_clause, fields, semi) = parse_data_struct(input)?;
+
+            if inherits.is_some() && !matches!(fields, SpecialFields::Named(_)) {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    "only structs with named fields can inherit fields",
+                ));
+            }
+
             Ok(Special {
                 attrs,
                 vis,
@@ -136,6 +237,7 @@ impl Parse for Special {
                 },
                 body: Body::Struct(BodyStruct {
                     struct_token,
+                    inherits,
                     fields,
                     semi_token: semi,
                 }),
@@ -234,10 +336,7 @@ impl Parse for SpecialVariant {
             SpecialFields::Unit
         };
         let discriminant = if input.peek(Token![=]) {
-            Some(Discriminant {
-                eq_token: input.parse()?,
-                expr: input.parse()?,
-            })
+            Some(input.parse()?)
         } else {
             None
         };
@@ -275,27 +374,42 @@ impl SpecialField {
         let attrs = input.call(FieldAttribute::parse_outer)?;
         let vis: Visibility = input.parse()?;
 
+        if input.peek(Token![..]) {
+            return Self::parse_spread(attrs, vis, input);
+        }
+
         // note: has cfg!(feature = "full") | data.rs
         let unnamed_field = input.peek(Token![_]);
 
-        let ident = if unnamed_field {
+        // string literal names are converted to a valid identifier: `"user-id"` -> `user_id`
+        let rename = if input.peek(LitStr) {
+            Some(input.parse::<LitStr>()?)
+        } else {
+            None
+        };
+
+        let ident = if let Some(rename) = &rename {
+            field_ident(&rename.value(), rename.span()).ok_or_else(|| {
+                syn::Error::new(
+                    rename.span(),
+                    "field name must contain at least one alphanumeric character",
+                )
+            })
+        } else if unnamed_field {
             input.call(Ident::parse_any)
         } else {
             input.parse()
         }?;
 
+        let question_token: Option<Token![?]> = input.parse()?;
         let colon_token: Token![:] = input.parse()?;
 
         let ty: SpecialType = if unnamed_field
             && (input.peek(Token![struct])
                 || input.peek(Token![union]) && input.peek2(token::Brace))
         {
-            let span = input.span();
-            abort!(
-                span,
-                "Not implemented Yet";
-                note = "Requires a rewrite of the syn::verbatim module"
-            ); // todo
+            // todo: requires a rewrite of the syn::verbatim module
+            return Err(syn::Error::new(input.span(), "anonymous `struct` and `union` fields are not supported"));
         } else {
             input.parse()?
         };
@@ -303,14 +417,62 @@ impl SpecialField {
         // handle FishHook
         let fish = handle_fish_hook(&mut input, &ty)?;
 
+        let default = parse_default(input)?;
+
         Ok(SpecialField {
             attrs,
             vis,
             mutability: FieldMutability::None,
             ident: Some(ident),
+            rename,
+            default,
+            question_token,
             fish,
             colon_token: Some(colon_token),
             ty,
+            spread: None,
+        })
+    }
+
+    /// Parses `..struct Name { .. }`, the field is named after the struct: `name`
+    fn parse_spread(attrs: Vec<FieldAttribute>, vis: Visibility, input: ParseStream) -> syn::Result<Self> {
+        let spread: Token![..] = input.parse()?;
+
+        if !input.peek(Token![struct]) {
+            return Err(input.error("expected an inline struct definition after `..`"));
+        }
+
+        let special: Special = input.parse()?;
+
+        if !matches!(
+            special.body,
+            Body::Struct(BodyStruct { fields: SpecialFields::Named(_), .. })
+        ) {
+            return Err(syn::Error::new(
+                special.ident.span(),
+                "only structs with named fields can be spread",
+            ));
+        }
+
+        let Some(ident) = field_ident(&special.ident.unraw().to_string(), special.ident.span()) else {
+            return Err(syn::Error::new_spanned(
+                &special.ident,
+                format!("cannot derive a field name from `{}`; name the spread field explicitly", special.ident),
+            ));
+        };
+
+        Ok(SpecialField {
+            attrs,
+            vis,
+            mutability: FieldMutability::None,
+            ident: Some(ident),
+            rename: None,
+            question_token: None,
+            colon_token: None,
+            ty: SpecialType::Def(special),
+            fish: None,
+            default: None,
+            spread: Some(spread),
         })
     }
 
@@ -322,18 +484,33 @@ impl SpecialField {
         // handle FishHook
         let fish = handle_fish_hook(&mut input, &ty)?;
 
+        let default = parse_default(input)?;
+
         Ok(SpecialField {
             attrs,
             vis,
             mutability: FieldMutability::None,
             ident: None,
+            rename: None,
+            question_token: None,
             colon_token: None,
             ty,
             fish,
+            default,
+            spread: None,
         })
     }
 }
 
+/// Parses the default value of a field: `= value`
+fn parse_default(input: ParseStream) -> syn::Result<Option<(Token![=], Expr)>> {
+    if input.peek(Token![=]) {
+        Ok(Some((input.parse()?, input.parse()?)))
+    } else {
+        Ok(None)
+    }
+}
+
 pub(crate) fn handle_fish_hook(input: &mut ParseStream, ty: &SpecialType) -> syn::Result<Option<FishHook>> {
     if input.peek(Token![||]) {
         // only allow FishHook syntax after a nested type definition
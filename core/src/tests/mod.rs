@@ -0,0 +1,8 @@
+mod test_attributes;
+mod test_case;
+mod test_discriminant;
+mod test_generate;
+#[cfg(feature = "json")]
+mod test_json;
+mod test_special_data;
+mod test_unpack;
@@ -0,0 +1,31 @@
+use crate::attributes::Attribute;
+use crate::directives::rejected;
+use syn::parse_quote;
+
+fn errors(attrs: Vec<Attribute>, position: &str) -> Vec<String> {
+    rejected(&attrs, position)
+        .iter()
+        .map(|error| error.message().to_string())
+        .collect()
+}
+
+#[test]
+fn other_attributes_are_kept() {
+    let attrs = vec![Attribute::new(parse_quote!(serde(rename = "id"))), Attribute::new(parse_quote!(doc = "id"))];
+
+    assert!(errors(attrs, "named fields").is_empty());
+}
+
+#[test]
+fn misplaced_directive() {
+    let attrs = vec![Attribute::new(parse_quote!(nest::only(request)))];
+
+    assert_eq!(errors(attrs, "variants"), ["`#[nest::only]` is not supported on variants"]);
+}
+
+#[test]
+fn unknown_directive() {
+    let attrs = vec![Attribute::new(parse_quote!(nest::sinse(v2)))];
+
+    assert_eq!(errors(attrs, "named fields"), ["unknown nestify attribute `nest::sinse`"]);
+}
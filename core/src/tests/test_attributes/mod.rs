@@ -0,0 +1,2 @@
+mod attribute_modifier;
+mod directives;
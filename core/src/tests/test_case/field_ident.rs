@@ -0,0 +1,40 @@
+use proc_macro2::Span;
+use crate::case::{field_ident, to_pascal_case, to_snake_case};
+
+fn ident(name: &str) -> String {
+    field_ident(name, Span::call_site()).unwrap().to_string()
+}
+
+#[test]
+fn snake_case_words() {
+    assert_eq!(to_snake_case("user-id"), "user_id");
+    assert_eq!(to_snake_case("userId"), "user_id");
+    assert_eq!(to_snake_case("HTTPStatus"), "http_status");
+    assert_eq!(to_snake_case("already_snake"), "already_snake");
+    assert_eq!(to_snake_case("per page"), "per_page");
+    assert_eq!(to_snake_case("@type"), "type");
+    assert_eq!(to_snake_case("version2Id"), "version2_id");
+}
+
+#[test]
+fn pascal_case_words() {
+    assert_eq!(to_pascal_case("request"), "Request");
+    assert_eq!(to_pascal_case("admin_view"), "AdminView");
+    assert_eq!(to_pascal_case("v2"), "V2");
+}
+
+#[test]
+fn ident_leading_number() {
+    assert_eq!(ident("2fa_enabled"), "_2fa_enabled");
+}
+
+#[test]
+fn ident_keyword() {
+    assert_eq!(ident("@type"), "r#type");
+    assert_eq!(ident("self"), "self_");
+}
+
+#[test]
+fn ident_without_alphanumerics() {
+    assert!(field_ident("--", Span::call_site()).is_none());
+}
@@ -0,0 +1,2 @@
+mod field_ident;
+mod rename_all;
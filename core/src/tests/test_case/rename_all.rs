@@ -0,0 +1,46 @@
+use crate::serde_attrs::{rename_field, rename_variant};
+
+#[test]
+fn field_cases() {
+    assert_eq!(rename_field("user_name", "camelCase"), "userName");
+    assert_eq!(rename_field("user_name", "PascalCase"), "UserName");
+    assert_eq!(rename_field("user_name", "kebab-case"), "user-name");
+    assert_eq!(rename_field("user_name", "SCREAMING_SNAKE_CASE"), "USER_NAME");
+    assert_eq!(rename_field("user_name", "SCREAMING-KEBAB-CASE"), "USER-NAME");
+    assert_eq!(rename_field("r#type", "camelCase"), "type");
+}
+
+#[test]
+fn variant_cases() {
+    assert_eq!(rename_variant("NotFound", "snake_case"), "not_found");
+    assert_eq!(rename_variant("NotFound", "camelCase"), "notFound");
+    assert_eq!(rename_variant("NotFound", "kebab-case"), "not-found");
+    assert_eq!(rename_variant("NotFound", "SCREAMING-KEBAB-CASE"), "NOT-FOUND");
+}
+
+#[test]
+fn case_of_name_as_written() {
+    assert_eq!(rename_variant("NotFound", "lowercase"), "notfound");
+    assert_eq!(rename_field("user_name", "UPPERCASE"), "USER_NAME");
+    // serde leaves field names as written in these cases
+    assert_eq!(rename_field("userName", "snake_case"), "userName");
+    assert_eq!(rename_field("user_Name", "lowercase"), "user_Name");
+}
+
+#[test]
+fn variant_acronyms() {
+    // every uppercase letter starts a word
+    assert_eq!(rename_variant("HTTPError", "snake_case"), "h_t_t_p_error");
+    assert_eq!(rename_variant("HTTPError", "SCREAMING_SNAKE_CASE"), "H_T_T_P_ERROR");
+    assert_eq!(rename_variant("HTTPError", "kebab-case"), "h-t-t-p-error");
+    assert_eq!(rename_variant("HTTPError", "camelCase"), "hTTPError");
+    assert_eq!(rename_variant("Error2FA", "snake_case"), "error2_f_a");
+}
+
+#[test]
+fn field_acronyms() {
+    assert_eq!(rename_field("http_url", "PascalCase"), "HttpUrl");
+    assert_eq!(rename_field("http_url", "camelCase"), "httpUrl");
+    assert_eq!(rename_field("user_2fa", "camelCase"), "user2fa");
+    assert_eq!(rename_field("_private", "PascalCase"), "Private");
+}
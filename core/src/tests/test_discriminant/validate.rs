@@ -0,0 +1,112 @@
+use crate::attributes::Attribute;
+use crate::discriminant::{literal_value, repr_type, validate};
+use crate::special_data::SpecialVariant;
+use quote::quote;
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::{parse_quote, Token};
+
+fn repr(attrs: &[Attribute]) -> Option<String> {
+    repr_type(attrs).map(|ident| ident.to_string())
+}
+
+fn errors(attrs: &[Attribute], variants: proc_macro2::TokenStream) -> Vec<String> {
+    let variants = Punctuated::<SpecialVariant, Token![,]>::parse_terminated
+        .parse2(variants)
+        .unwrap();
+
+    validate(attrs, &variants)
+        .iter()
+        .map(|error| error.message().to_string())
+        .collect()
+}
+
+#[test]
+fn literal_values() {
+    assert_eq!(literal_value(&parse_quote!(1)), Some(1));
+    assert_eq!(literal_value(&parse_quote!(-1)), Some(-1));
+    assert_eq!(literal_value(&parse_quote!(0xff)), Some(255));
+    assert_eq!(literal_value(&parse_quote!(1u8)), Some(1));
+    assert_eq!(literal_value(&parse_quote!(b'a')), Some(97));
+    assert_eq!(literal_value(&parse_quote!((-(2)))), Some(-2));
+}
+
+#[test]
+fn non_literal_values() {
+    assert_eq!(literal_value(&parse_quote!(CONST)), None);
+    assert_eq!(literal_value(&parse_quote!(1 + 1)), None);
+}
+
+#[test]
+fn integer_repr() {
+    assert_eq!(repr(&[Attribute::new(parse_quote!(repr(u8)))]), Some("u8".to_string()));
+    assert_eq!(repr(&[Attribute::new(parse_quote!(repr(C, i32)))]), Some("i32".to_string()));
+    assert_eq!(
+        repr(&[
+            Attribute::new(parse_quote!(derive(Clone))),
+            Attribute::new(parse_quote!(repr(u16))),
+        ]),
+        Some("u16".to_string())
+    );
+}
+
+#[test]
+fn no_integer_repr() {
+    assert_eq!(repr(&[]), None);
+    assert_eq!(repr(&[Attribute::new(parse_quote!(repr(C)))]), None);
+    assert_eq!(repr(&[Attribute::new(parse_quote!(repr(align(8))))]), None);
+}
+
+#[test]
+fn valid_discriminants() {
+    assert!(errors(&[], quote!(A = 1, B, C = 5)).is_empty());
+    assert!(errors(&[Attribute::new(parse_quote!(repr(u8)))], quote!(A = 255, B(u8) = 0)).is_empty());
+    assert!(errors(&[], quote!(A = "a", B = "b", C)).is_empty());
+}
+
+#[test]
+fn duplicate_discriminants() {
+    assert_eq!(
+        errors(&[], quote!(A = 1, B = 1)),
+        ["discriminant value `1` of `B` is already assigned to `A`"]
+    );
+    assert_eq!(
+        errors(&[], quote!(A, B, C = 1)),
+        ["discriminant value `1` of `C` is already assigned to `B`"]
+    );
+}
+
+#[test]
+fn duplicate_string_values() {
+    assert_eq!(
+        errors(&[], quote!(A = "x", B = "x")),
+        ["string value \"x\" of `B` is already assigned to `A`"]
+    );
+    assert_eq!(
+        errors(&[], quote!(A = "B", B)),
+        ["string value \"B\" of `B` is already assigned to `A`"]
+    );
+}
+
+#[test]
+fn out_of_range_discriminants() {
+    assert_eq!(
+        errors(&[Attribute::new(parse_quote!(repr(u8)))], quote!(A = 255, B)),
+        ["discriminant value `256` of `B` is out of range for `#[repr(u8)]`"]
+    );
+    assert_eq!(
+        errors(&[Attribute::new(parse_quote!(repr(u8)))], quote!(A = -1)),
+        ["discriminant value `-1` of `A` is out of range for `#[repr(u8)]`"]
+    );
+}
+
+#[test]
+fn discriminants_with_fields_without_repr() {
+    assert_eq!(
+        errors(&[], quote!(A(u8) = 1, B = 2)),
+        [
+            "explicit discriminants on enums with fields require an integer `#[repr]`",
+            "explicit discriminants on enums with fields require an integer `#[repr]`",
+        ]
+    );
+}
@@ -0,0 +1,76 @@
+use crate::generate::{classify, EnumRepr, Primitive, TypeKind};
+use crate::serde_attrs::SerdeAttrs;
+use syn::{parse_quote, Type};
+
+fn kind(ty: Type) -> String {
+    match classify(&ty) {
+        TypeKind::Primitive(Primitive::Bool) => "bool".to_string(),
+        TypeKind::Primitive(Primitive::Char) => "char".to_string(),
+        TypeKind::Primitive(Primitive::String) => "string".to_string(),
+        TypeKind::Primitive(Primitive::Int { signed, bits }) => format!("int {signed} {bits}"),
+        TypeKind::Primitive(Primitive::Float { bits }) => format!("float {bits}"),
+        TypeKind::Unit => "unit".to_string(),
+        TypeKind::Optional(_) => "optional".to_string(),
+        TypeKind::Sequence(_) => "sequence".to_string(),
+        TypeKind::Set(_) => "set".to_string(),
+        TypeKind::Map(..) => "map".to_string(),
+        TypeKind::Tuple(types) => format!("tuple {}", types.len()),
+        TypeKind::Named(name, args) => format!("{name} {}", args.len()),
+        TypeKind::Unknown => "unknown".to_string(),
+    }
+}
+
+#[test]
+fn primitives() {
+    assert_eq!(kind(parse_quote!(std::path::PathBuf)), "string");
+    assert_eq!(kind(parse_quote!(&'a str)), "string");
+    assert_eq!(kind(parse_quote!(usize)), "int false 64");
+    assert_eq!(kind(parse_quote!(i128)), "int true 128");
+    assert_eq!(kind(parse_quote!(f32)), "float 32");
+}
+
+#[test]
+fn pointers_are_classified_as_their_target() {
+    for ty in [
+        parse_quote!(Box<u8>),
+        parse_quote!(std::rc::Rc<u8>),
+        parse_quote!(Arc<u8>),
+        parse_quote!(Cow<'a, u8>),
+        parse_quote!(RefCell<u8>),
+        parse_quote!(Cell<u8>),
+    ] {
+        assert_eq!(kind(ty), "int false 8");
+    }
+}
+
+#[test]
+fn collections() {
+    assert_eq!(kind(parse_quote!(Option<String>)), "optional");
+    assert_eq!(kind(parse_quote!(VecDeque<String>)), "sequence");
+    assert_eq!(kind(parse_quote!([u8; 4])), "sequence");
+    assert_eq!(kind(parse_quote!(BTreeSet<String>)), "set");
+    assert_eq!(kind(parse_quote!(std::collections::HashMap<String, u8>)), "map");
+    assert_eq!(kind(parse_quote!((u8, String))), "tuple 2");
+    assert_eq!(kind(parse_quote!(())), "unit");
+    assert_eq!(kind(parse_quote!(Page<r#Item>)), "Page 1");
+    assert_eq!(kind(parse_quote!(fn())), "unknown");
+}
+
+#[test]
+fn enum_representations() {
+    let repr = |serde: SerdeAttrs| match EnumRepr::from(&serde) {
+        EnumRepr::External => "external".to_string(),
+        EnumRepr::Internal { tag } => format!("internal {tag}"),
+        EnumRepr::Adjacent { tag, content } => format!("adjacent {tag} {content}"),
+        EnumRepr::Untagged => "untagged".to_string(),
+    };
+    let tag = || Some("type".to_string());
+
+    assert_eq!(repr(SerdeAttrs::default()), "external");
+    assert_eq!(repr(SerdeAttrs { tag: tag(), ..SerdeAttrs::default() }), "internal type");
+    assert_eq!(
+        repr(SerdeAttrs { tag: tag(), content: Some("data".to_string()), ..SerdeAttrs::default() }),
+        "adjacent type data"
+    );
+    assert_eq!(repr(SerdeAttrs { tag: tag(), untagged: true, ..SerdeAttrs::default() }), "untagged");
+}
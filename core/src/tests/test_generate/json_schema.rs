@@ -0,0 +1,43 @@
+use quote::quote;
+use serde_json::{json, Value};
+use super::{expand, generated};
+
+#[test]
+fn schema_of_nested_types() {
+    let expansion = expand(quote!(
+        #[nest::json_schema]
+        /// A registered user
+        struct User {
+            name: String,
+            nickname: Option<String>,
+            friends: Vec<User>,
+            address: struct Address {
+                city: String,
+            },
+            status: enum Status {
+                Active,
+                Banned,
+            },
+            event: enum Event {
+                Login { at: u64 },
+                Logout,
+            },
+        }
+    ));
+
+    let schema: Value = serde_json::from_str(&generated(expansion, "json_schema")).unwrap();
+
+    assert_eq!(schema["title"], "User");
+    assert_eq!(schema["description"], "A registered user");
+    assert_eq!(schema["required"], json!(["name", "friends", "address", "status", "event"]));
+    assert_eq!(schema["properties"]["nickname"]["type"], json!(["string", "null"]));
+    assert_eq!(schema["properties"]["friends"]["items"], json!({ "$ref": "#" }));
+    assert_eq!(schema["properties"]["address"], json!({ "$ref": "#/$defs/Address" }));
+    assert_eq!(schema["$defs"]["Address"]["required"], json!(["city"]));
+    assert_eq!(schema["$defs"]["Status"], json!({ "type": "string", "enum": ["Active", "Banned"] }));
+    assert_eq!(schema["$defs"]["Event"]["oneOf"][1], json!({ "const": "Logout" }));
+    assert_eq!(
+        schema["$defs"]["Event"]["oneOf"][0]["properties"]["Login"]["required"],
+        json!(["at"])
+    );
+}
@@ -0,0 +1,39 @@
+mod classify;
+#[cfg(feature = "json")]
+mod json_schema;
+mod proto;
+mod typescript;
+
+use proc_macro2::{TokenStream, TokenTree};
+
+/// Expands `input` like `nest!` does
+fn expand(input: TokenStream) -> TokenStream {
+    crate::expand(crate::parse(input).unwrap())
+}
+
+/// The string generated for the item `name`, the first string literal after it
+fn generated(expansion: TokenStream, name: &str) -> String {
+    fn flatten(tokens: TokenStream, flat: &mut Vec<TokenTree>) {
+        for token in tokens {
+            match token {
+                TokenTree::Group(group) => flatten(group.stream(), flat),
+                token => flat.push(token),
+            }
+        }
+    }
+
+    let mut tokens = vec![];
+    flatten(expansion, &mut tokens);
+
+    let mut after = tokens
+        .into_iter()
+        .skip_while(|token| !matches!(token, TokenTree::Ident(ident) if ident == name));
+    let literal = after
+        .find_map(|token| match token {
+            TokenTree::Literal(literal) => Some(literal),
+            _ => None,
+        })
+        .unwrap_or_else(|| panic!("`{name}` is not generated"));
+
+    syn::parse2::<syn::LitStr>(TokenTree::Literal(literal).into()).unwrap().value()
+}
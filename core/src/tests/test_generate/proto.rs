@@ -0,0 +1,61 @@
+use quote::quote;
+use super::{expand, generated};
+
+#[test]
+fn messages_of_nested_types() {
+    let expansion = expand(quote!(
+        #[nest::proto]
+        struct User {
+            name: String,
+            nickname: Option<String>,
+            #[nest::tag(5)]
+            tags: Vec<String>,
+            address: struct Address {
+                city: String,
+            },
+            status: enum Status {
+                Active,
+                Banned,
+            },
+            event: enum Event {
+                Login { at: u64 },
+                Renamed(String),
+            },
+        }
+    ));
+
+    assert_eq!(
+        generated(expansion, "PROTO"),
+        r#"syntax = "proto3";
+
+message User {
+  message Address {
+    string city = 1;
+  }
+
+  enum Status {
+    STATUS_ACTIVE = 0;
+    STATUS_BANNED = 1;
+  }
+
+  message Event {
+    message Login {
+      uint64 at = 1;
+    }
+
+    oneof event {
+      Login login = 1;
+      string renamed = 2;
+    }
+  }
+
+  string name = 1;
+  optional string nickname = 2;
+  repeated string tags = 5;
+  Address address = 6;
+  Status status = 7;
+  Event event = 8;
+}
+"#
+    );
+}
@@ -0,0 +1,32 @@
+use quote::quote;
+use super::{expand, generated};
+
+#[test]
+fn declarations() {
+    let expansion = expand(quote!(
+        #[nest::typescript]
+        #[nest::serde]*
+        #[derive(Serialize)]*
+        #[serde(rename_all = "camelCase")]*
+        struct User {
+            user_name: String,
+            nickname?: String,
+            tags: Vec<String>,
+            profile: struct Profile {
+                age_years: u32,
+            },
+            #[serde(rename = "kind")]
+            role: #[serde(tag = "type")] enum Role {
+                Admin,
+                Guest { expires: Option<u64> },
+            },
+        }
+    ));
+
+    let user = "export interface User {\n  userName: string;\n  nickname?: string;\n  tags: string[];\n  profile: Profile;\n  kind: Role;\n}";
+    let profile = "export interface Profile {\n  ageYears: number;\n}";
+    let role = "export type Role =\n  | { type: \"admin\" }\n  | ({ type: \"guest\" } & { expires: number | null });";
+
+    // the declarations of the nested types follow the one of the outermost type
+    assert_eq!(generated(expansion, "TS_DECLS"), [user, profile, role].join("\n\n"));
+}
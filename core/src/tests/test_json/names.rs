@@ -0,0 +1,72 @@
+use crate::json::{special, type_name, JsonInput};
+use crate::{openapi, schema};
+use crate::special_data::Special;
+use proc_macro2::Span;
+use quote::quote;
+
+fn input() -> JsonInput {
+    syn::parse2(quote!(Root, "")).unwrap()
+}
+
+fn idents(mut special: Special) -> Vec<String> {
+    let mut idents = vec![];
+    special.visit(&mut |special| idents.push(special.ident.to_string()));
+    idents
+}
+
+fn names(json: &str) -> Vec<String> {
+    idents(special(input(), json, Span::call_site()))
+}
+
+fn schema_names(json: &str) -> Vec<String> {
+    idents(schema::special(input(), json, Span::call_site()))
+}
+
+#[test]
+fn names_of_keys() {
+    assert_eq!(type_name("user_profile", "Root"), "UserProfile");
+    assert_eq!(type_name("2fa", "User"), "User2fa");
+}
+
+#[test]
+fn names_of_keywords() {
+    assert_eq!(type_name("self", "Root"), "Self_");
+    assert_eq!(type_name("Self", "Root"), "Self_");
+    assert_eq!(names(r#"{ "self": { "id": 1 } }"#), ["Root", "Self_"]);
+}
+
+#[test]
+fn different_definitions_under_the_same_key() {
+    assert_eq!(
+        names(r#"{ "a": { "data": { "x": 1 } }, "b": { "data": { "y": "s" } } }"#),
+        ["Root", "A", "AData", "B", "BData"]
+    );
+}
+
+#[test]
+fn identical_definitions_under_the_same_key() {
+    assert_eq!(
+        names(r#"{ "a": { "data": { "x": 1 } }, "b": { "data": { "x": 2 } } }"#),
+        ["Root", "A", "Data", "B", "Data"]
+    );
+}
+
+#[test]
+fn different_schemas_under_the_same_key() {
+    let data = |ty: &str| format!(r#"{{ "properties": {{ "data": {{ "properties": {{ "x": {{ "type": "{ty}" }} }} }} }} }}"#);
+    let schema = format!(r#"{{ "properties": {{ "a": {}, "b": {} }} }}"#, data("integer"), data("string"));
+
+    assert_eq!(schema_names(&schema), ["Root", "A", "AData", "B", "BData"]);
+}
+
+#[test]
+fn different_schemas_of_components_under_the_same_key() {
+    let input = syn::parse2(quote!("api.json", components = [User, Order])).unwrap();
+    let document = r#"{ "components": { "schemas": {
+        "User": { "properties": { "address": { "properties": { "city": { "type": "string" } } } } },
+        "Order": { "properties": { "address": { "properties": { "street": { "type": "string" } } } } }
+    } } }"#;
+
+    let names: Vec<Vec<String>> = openapi::specials(input, document).into_iter().map(idents).collect();
+    assert_eq!(names, [["User", "UserAddress"], ["Order", "OrderAddress"]]);
+}
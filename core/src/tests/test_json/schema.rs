@@ -0,0 +1,48 @@
+use crate::json::Names;
+use crate::schema::Schema;
+use proc_macro2::Span;
+use serde_json::{json, Value};
+
+/// The type of `value` and the errors reported for it
+fn ty(value: Value) -> (String, Vec<String>) {
+    let mut names = Names::default();
+    let mut schema = Schema {
+        root: &value,
+        span: Span::call_site(),
+        refs: vec![],
+        shared: vec![],
+        names: &mut names,
+        errors: vec![],
+    };
+
+    let (ty, _) = schema.ty(&value, "Root");
+    let errors = schema.errors.iter().map(|error| error.message().to_string()).collect();
+    (ty.to_string(), errors)
+}
+
+#[test]
+fn typed_values() {
+    assert_eq!(ty(json!({ "type": "integer", "format": "int32" })), ("i32".to_string(), vec![]));
+    assert_eq!(ty(json!({ "type": "null" })), ("()".to_string(), vec![]));
+    assert_eq!(
+        ty(json!({ "type": "object", "additionalProperties": { "type": "string" } })).1,
+        Vec::<String>::new()
+    );
+}
+
+#[test]
+fn untyped_values() {
+    assert_eq!(ty(json!({})).1, ["cannot define a type for `Root`, from a schema without `type`"]);
+    assert_eq!(
+        ty(json!({ "type": "array" })).1,
+        ["cannot define a type for `RootItem`, from a schema without `type`"]
+    );
+    assert_eq!(
+        ty(json!({ "type": "object" })).1,
+        ["cannot define a type for `Root`, from an object without `properties` or a schema of `additionalProperties`"]
+    );
+    assert_eq!(
+        ty(json!({ "type": "file" })).1,
+        ["cannot define a type for `Root`, from a schema of type `file`"]
+    );
+}
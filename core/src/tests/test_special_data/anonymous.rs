@@ -0,0 +1,17 @@
+use crate::special_data::Special;
+use quote::quote;
+
+#[test]
+fn anonymous_struct_field() {
+    // reported by `parse`, outside of a procedural macro
+    let err = crate::parse(quote!(struct User { _: struct { id: u64 } })).err().unwrap();
+
+    assert_eq!(err.to_string(), "anonymous `struct` and `union` fields are not supported");
+}
+
+#[test]
+fn anonymous_union_field() {
+    let err = syn::parse2::<Special>(quote!(struct User { _: union { id: u64 } })).err().unwrap();
+
+    assert_eq!(err.to_string(), "anonymous `struct` and `union` fields are not supported");
+}
@@ -0,0 +1,2 @@
+mod anonymous;
+mod spread;
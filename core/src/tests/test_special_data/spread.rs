@@ -0,0 +1,23 @@
+use crate::special_data::{Body, BodyStruct, Special, SpecialFields};
+use quote::quote;
+
+#[test]
+fn field_named_after_spread_struct() {
+    let special: Special = syn::parse2(quote!(struct User { ..struct UserMeta { id: u64 } })).unwrap();
+
+    let Body::Struct(BodyStruct { fields: SpecialFields::Named(named), .. }) = special.body else {
+        panic!("expected a struct with named fields");
+    };
+    let field = named.named.first().unwrap();
+    assert_eq!(field.ident.as_ref().unwrap(), "user_meta");
+    assert!(field.spread.is_some());
+}
+
+#[test]
+fn spread_struct_without_field_name() {
+    let err = syn::parse2::<Special>(quote!(struct User { ..struct __ { id: u64 } }))
+        .err()
+        .unwrap();
+
+    assert_eq!(err.to_string(), "cannot derive a field name from `__`; name the spread field explicitly");
+}
@@ -0,0 +1,62 @@
+use proc_macro2::Span;
+use syn::Ident;
+use crate::directives::Directives;
+use crate::registry::{DefinitionKind, Registration, Registry};
+
+fn ident(name: &str) -> Ident {
+    Ident::new(name, Span::call_site())
+}
+
+fn register(registry: &mut Registry, name: &str, key: &str, dedup: bool) -> Registration {
+    let directives = Directives {
+        dedup,
+        ..Directives::default()
+    };
+
+    registry.register(&ident(name), key.to_string(), DefinitionKind::NamedStruct, &directives)
+}
+
+#[test]
+fn register_distinct_names() {
+    let mut registry = Registry::default();
+
+    assert!(matches!(register(&mut registry, "A", "struct A ;", false), Registration::Fresh));
+    assert!(matches!(register(&mut registry, "B", "struct B ;", false), Registration::Fresh));
+}
+
+#[test]
+fn register_duplicate_name_conflicts() {
+    let mut registry = Registry::default();
+
+    register(&mut registry, "Item", "struct Item ;", false);
+
+    assert!(matches!(register(&mut registry, "Item", "struct Item ;", false), Registration::Conflict(_)));
+}
+
+#[test]
+fn register_identical_definition_merges() {
+    let mut registry = Registry::default();
+
+    register(&mut registry, "Item", "struct Item { a : u32 }", true);
+
+    assert!(matches!(register(&mut registry, "Item", "struct Item { a : u32 }", true), Registration::Merged));
+}
+
+#[test]
+fn register_different_definition_conflicts_with_dedup() {
+    let mut registry = Registry::default();
+
+    register(&mut registry, "Item", "struct Item { a : u32 }", true);
+
+    assert!(matches!(register(&mut registry, "Item", "struct Item { b : u32 }", true), Registration::Conflict(_)));
+}
+
+#[test]
+fn lookup_registered_definition() {
+    let mut registry = Registry::default();
+
+    register(&mut registry, "Item", "struct Item ;", false);
+
+    assert!(registry.get(&ident("Item")).is_some_and(|entry| entry.kind == DefinitionKind::NamedStruct));
+    assert!(registry.get(&ident("Other")).is_none());
+}
@@ -0,0 +1,157 @@
+//! Twins are definitions derived from a struct with named fields and the structs nested in it,
+//! such as [partial twins](crate::partial) and [views](crate::views).
+//!
+//! The outermost struct builds its twins from the parsed tree, and they are unpacked like
+//! any other definition. Nested structs with named fields get twins of their own as part of
+//! them, so while unpacking the original tree, [`Twins`] is passed down to let them generate
+//! the code that connects them to their twins.
+
+use crate::attributes::{CompositeAttribute, FieldAttribute};
+use crate::directives::{is_directive, Directives};
+use crate::special_data::{Body, BodyStruct, Special, SpecialField, SpecialFields};
+use crate::ty::SpecialType;
+use crate::{partial, views};
+use proc_macro_error::emit_error;
+use quote::format_ident;
+use syn::ext::IdentExt;
+use syn::{Generics, Ident, Visibility};
+
+/// Directives that still apply to twins, every other directive only applies to the original
+const TWIN_DIRECTIVES: &[&str] = &["dedup", "serde"];
+
+/// The twins of a struct
+#[derive(Clone, Default)]
+pub(crate) struct Twins {
+    /// The suffix of the partial twins
+    pub partial: Option<String>,
+    /// The views the struct is part of
+    pub views: Vec<Ident>,
+    /// Pairs of views `(from, into)`, where every field of `into` is also a field of `from`
+    pub conversions: Vec<(Ident, Ident)>,
+}
+
+impl Twins {
+    /// Determines the twins of a definition, from its own directives or from the twins of the
+    /// struct it is nested in. Returns the twins to unpack if it is the outermost struct.
+    pub fn resolve(
+        ident: &Ident,
+        vis: &Visibility,
+        generics: &Generics,
+        attrs: Vec<CompositeAttribute>,
+        body: &Body,
+        directives: &Directives,
+        inherited: Twins,
+    ) -> (Twins, Vec<Special>) {
+        let fields = match body {
+            Body::Struct(BodyStruct { fields: fields @ SpecialFields::Named(_), .. }) => Some(fields),
+            _ => None,
+        };
+
+        let mut twins = Twins::default();
+        let mut definitions = vec![];
+
+        // `#[nest::partial(Name)]`
+        match (&directives.partial, fields) {
+            (Some(name), Some(fields)) => {
+                if let Some(suffix) = partial::suffix(ident, name) {
+                    definitions.push(partial::twin(name, vis, generics, &attrs, fields, &suffix));
+                    twins.partial = Some(suffix);
+                }
+            }
+            (Some(name), None) => {
+                emit_error!(name.span(), "only structs with named fields can have a partial twin")
+            }
+            (None, Some(_)) => twins.partial = inherited.partial,
+            (None, None) => {}
+        }
+
+        // `#[nest::views(...)]`
+        match (directives.views.as_slice(), fields) {
+            ([], Some(_)) => {
+                twins.views = inherited.views;
+                twins.conversions = inherited.conversions;
+            }
+            ([], None) => {}
+            (views, Some(fields)) => {
+                for view in views {
+                    let name = twin_ident(ident, &views::suffix(view));
+                    definitions.push(views::twin(&name, vis, generics, &attrs, fields, view));
+                }
+                twins.conversions = views::conversions(fields, views);
+                twins.views = views.to_vec();
+            }
+            ([view, ..], None) => {
+                emit_error!(view.span(), "only structs with named fields can have views")
+            }
+        }
+
+        (twins, definitions)
+    }
+
+    /// The twins of the struct defined as the type of `field`
+    pub fn nested(&self, field: &SpecialField) -> Twins {
+        match &field.ty {
+            SpecialType::Def(special) if has_twin(field, special) => {
+                let only = views::only(&field.attrs);
+                let views: Vec<Ident> = self
+                    .views
+                    .iter()
+                    .filter(|view| only.is_empty() || only.contains(view))
+                    .cloned()
+                    .collect();
+
+                Twins {
+                    partial: self.partial.clone(),
+                    conversions: self
+                        .conversions
+                        .iter()
+                        .filter(|(from, into)| views.contains(from) && views.contains(into))
+                        .cloned()
+                        .collect(),
+                    views,
+                }
+            }
+            _ => Twins::default(),
+        }
+    }
+}
+
+/// The name of a twin: `Address` -> `AddressPatch`
+pub(crate) fn twin_ident(ident: &Ident, suffix: &str) -> Ident {
+    format_ident!("{}{}", ident.unraw(), suffix, span = ident.span())
+}
+
+/// Returns `true` if the struct defined as the type of `field` gets twins of its own.
+/// Optional structs and everything that isn't a struct with named fields is reused as is.
+pub(crate) fn has_twin(field: &SpecialField, special: &Special) -> bool {
+    field.question_token.is_none()
+        && matches!(
+            special.body,
+            Body::Struct(BodyStruct { fields: SpecialFields::Named(_), .. })
+        )
+}
+
+/// The attributes of a twin, without the directives that only apply to the original
+pub(crate) fn twin_attrs(attrs: &[CompositeAttribute]) -> Vec<CompositeAttribute> {
+    attrs
+        .iter()
+        .filter(|attr| {
+            let path = attr.meta.path();
+            !is_directive(path) || TWIN_DIRECTIVES.iter().any(|name| path.segments[1].ident == name)
+        })
+        .cloned()
+        .collect()
+}
+
+/// The attributes of a field of a twin, without directives.
+/// `#>[...]` is only kept if the field still defines a type.
+pub(crate) fn twin_field_attrs(attrs: &[FieldAttribute], nested: bool) -> Vec<FieldAttribute> {
+    attrs
+        .iter()
+        .filter(|attr| match attr {
+            FieldAttribute::Field(attr) => !is_directive(attr.meta.path()),
+            FieldAttribute::Nested(_) => nested,
+        })
+        .cloned()
+        .collect()
+}
@@ -1,5 +1,5 @@
 use {
-    crate::special_data::Special,
+    crate::{fish::FishHook, special_data::Special},
     syn::{
         parse::{Parse, ParseStream},
         Type,
@@ -7,6 +7,8 @@ use {
 };
 
 /// Can either be a normal type, or a type definition
+#[derive(Clone)]
+#[non_exhaustive]
 pub enum SpecialType {
     /// Our curstom `struct`/`enum` syntax
     Def(Special),
@@ -29,6 +31,19 @@ impl Parse for SpecialType {
     }
 }
 
+impl SpecialType {
+    /// The type without any definitions in it: definitions are replaced by their name
+    pub fn reference(&self, fish: &Option<FishHook>) -> Type {
+        match self {
+            SpecialType::Def(special) => {
+                augmented::type_from_ident_and_fish(special.ident.clone(), fish.clone())
+            }
+            SpecialType::Augmented(ty) => ty.reference(),
+            SpecialType::Type(ty) => ty.clone(),
+        }
+    }
+}
+
 /// Re-implementation of syn types to support nested custom syntax.
 pub(crate) mod augmented {
     use {
@@ -42,25 +57,30 @@ pub(crate) mod augmented {
         proc_macro2::TokenStream,
     };
 
+    #[derive(Clone)]
     pub enum Type {
         Path(TypePath),
     }
 
+    #[derive(Clone)]
     pub struct TypePath {
         pub qself: Option<syn::QSelf>,
         pub path: Path,
     }
 
+    #[derive(Clone)]
     pub struct Path {
         pub leading_colon: Option<syn::token::PathSep>,
         pub segments: syn::punctuated::Punctuated<PathSegment, syn::token::PathSep>,
     }
 
+    #[derive(Clone)]
     pub struct PathSegment {
         pub ident: syn::Ident,
         pub arguments: PathArguments,
     }
 
+    #[derive(Clone)]
     pub enum PathArguments {
         None,
         AngleBracketed(AngleBracketedGenericArguments),
@@ -68,6 +88,7 @@ pub(crate) mod augmented {
         // Parenthesized(syn::ParenthesizedGenericArguments),
     }
 
+    #[derive(Clone)]
     pub struct AngleBracketedGenericArguments {
         pub colon2_token: Option<syn::token::PathSep>,
         pub lt_token: syn::token::Lt,
@@ -75,6 +96,7 @@ pub(crate) mod augmented {
         pub gt_token: syn::token::Gt,
     }
 
+    #[derive(Clone)]
     pub enum GenericArgument {
         Lifetime(syn::Lifetime),
         // here we replace `syn::Type` by `super::SpecialType` !
@@ -86,6 +108,32 @@ pub(crate) mod augmented {
     }
 
     impl Type {
+        /// Returns the name and type of the definition if this is `Option<struct ...>`
+        pub fn optional_definition(&self) -> Option<(syn::Ident, syn::Type)> {
+            let Type::Path(TypePath { qself: None, path }) = self else {
+                return None;
+            };
+            let segment = path.segments.last()?;
+
+            if segment.ident != "Option" {
+                return None;
+            }
+
+            let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+                return None;
+            };
+
+            match arguments.args.first() {
+                Some(GenericArgument::Type(super::SpecialType::Def(special), fish))
+                    if arguments.args.len() == 1 =>
+                {
+                    let ty = type_from_ident_and_fish(special.ident.clone(), fish.clone());
+                    Some((special.ident.clone(), ty))
+                }
+                _ => None,
+            }
+        }
+
         /// Returns Ok(None) if it doesn't match
         pub fn maybe_parse(input: ParseStream) -> Result<Option<Self>> {
             let lookahead = input.lookahead1();
@@ -423,6 +471,73 @@ pub(crate) mod augmented {
         }
     }
 
+    // references, see `SpecialType::reference`
+    impl Type {
+        pub fn reference(&self) -> syn::Type {
+            match self {
+                Type::Path(TypePath { qself, path }) => syn::Type::Path(syn::TypePath {
+                    qself: qself.clone(),
+                    path: path.reference(),
+                }),
+            }
+        }
+    }
+
+    impl Path {
+        fn reference(&self) -> syn::Path {
+            let segments = self
+                .segments
+                .pairs()
+                .map(|pair| {
+                    let (segment, punct) = pair.into_tuple();
+                    syn::punctuated::Pair::new(segment.reference(), punct.cloned())
+                })
+                .collect();
+
+            syn::Path { leading_colon: self.leading_colon, segments }
+        }
+    }
+
+    impl PathSegment {
+        fn reference(&self) -> syn::PathSegment {
+            let arguments = match &self.arguments {
+                PathArguments::None => syn::PathArguments::None,
+                PathArguments::AngleBracketed(arguments) => {
+                    let args = arguments
+                        .args
+                        .pairs()
+                        .map(|pair| {
+                            let (arg, punct) = pair.into_tuple();
+                            syn::punctuated::Pair::new(arg.reference(), punct.cloned())
+                        })
+                        .collect();
+
+                    syn::PathArguments::AngleBracketed(syn::AngleBracketedGenericArguments {
+                        colon2_token: arguments.colon2_token,
+                        lt_token: arguments.lt_token,
+                        args,
+                        gt_token: arguments.gt_token,
+                    })
+                }
+            };
+
+            syn::PathSegment { ident: self.ident.clone(), arguments }
+        }
+    }
+
+    impl GenericArgument {
+        fn reference(&self) -> syn::GenericArgument {
+            match self {
+                GenericArgument::Lifetime(v) => syn::GenericArgument::Lifetime(v.clone()),
+                GenericArgument::Type(ty, fish) => syn::GenericArgument::Type(ty.reference(fish)),
+                GenericArgument::Const(v) => syn::GenericArgument::Const(v.clone()),
+                GenericArgument::AssocType(v) => syn::GenericArgument::AssocType(v.clone()),
+                GenericArgument::AssocConst(v) => syn::GenericArgument::AssocConst(v.clone()),
+                GenericArgument::Constraint(v) => syn::GenericArgument::Constraint(v.clone()),
+            }
+        }
+    }
+
     // unpack
     impl Unpack for Type {
         type Output = (syn::Type, Vec<TokenStream>);
@@ -575,7 +690,7 @@ pub(crate) mod augmented {
         }
     }
 
-    fn type_from_ident_and_fish(ident: syn::Ident, fish: Option<FishHook>) -> syn::Type {
+    pub(crate) fn type_from_ident_and_fish(ident: syn::Ident, fish: Option<FishHook>) -> syn::Type {
         let args = match fish {
             None => syn::PathArguments::None,
             Some(fish) => {
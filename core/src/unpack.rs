@@ -0,0 +1,679 @@
+use crate::special_data::{
+    Body, BodyStruct, FieldsNamed, FieldsUnnamed, Special, SpecialField, SpecialFields,
+};
+use crate::ty::augmented::type_from_ident_and_fish;
+use crate::ty::SpecialType;
+use crate::unpack_context::UnpackContext;
+use proc_macro2::TokenStream;
+use proc_macro_error::emit_error;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{parse_quote, Expr, Ident, LitInt, LitStr, Type, Visibility};
+use crate::attributes::{Attribute, CompositeAttribute};
+use crate::directives::{self, Directives};
+use crate::discriminant::{self, Discriminant};
+use crate::generate::{self, proto, Definition, UnpackedBody};
+use crate::twin::Twins;
+use crate::views;
+use crate::registry::DefinitionKind;
+
+/// A trait for types that can be unpacked within the context of custom attribute processing.
+///
+/// Implementors of this trait can be "unpacked" to generate Rust code (as `TokenStream`)
+/// based on their structure and annotations, potentially including modifications
+/// influenced by a provided `UnpackContext`.
+pub(crate) trait Unpack {
+    type Output;
+
+    /// Unpacks the current structure into a Rust `TokenStream`, taking into account
+    /// modifications from the given `UnpackContext` and any additional attributes.
+    ///
+    /// # Parameters
+    /// - `self`: The instance of the implementor to unpack.
+    /// - `context`: The unpacking context carrying information about inherited attributes
+    ///   and possibly influencing how unpacking is performed.
+    /// - `next`: A collection of `CompositeAttribute` that may modify the behavior of
+    ///   unpacking or influence the generated output.
+    /// - `override_public`: A modifier that is used to force the next
+    ///    definition to be specific publicity.
+    /// - `enum_context`: A boolean stating if an unnamed field is in an enum variant or not
+    ///
+    /// # Returns
+    /// `Self::Output`: The generated Rust code as a `TokenStream`.
+    fn unpack(self, context: UnpackContext, next: Vec<CompositeAttribute>, override_public: Option<Visibility>, enum_context: bool) -> Self::Output;
+}
+
+/// A field after unpacking, as seen by the generators in [`generate`]
+pub(crate) struct UnpackedField {
+    /// Name of the field, `None` for unnamed fields
+    pub ident: Option<Ident>,
+    /// The type the field is emitted with
+    pub ty: Type,
+    /// Declared default value: `field: T = value`
+    pub default: Option<Expr>,
+    /// The type of the field is `Option<_>`
+    pub optional: bool,
+    /// The definition written inline as the type of the field,
+    /// either directly or as `Option<struct ...>`
+    pub inline: Option<InlineDefinition>,
+    /// The field is a spread struct embedded with `#[serde(flatten)]`
+    pub flatten: bool,
+    /// The views the field belongs to, from `#[nest::only(...)]`, empty for all views
+    pub only: Vec<Ident>,
+    /// The field number set with `#[nest::tag(n)]`
+    pub tag: Option<LitInt>,
+    /// The attributes the field is emitted with
+    pub attrs: Vec<Attribute>,
+}
+
+/// An enum variant after unpacking
+pub(crate) struct UnpackedVariant {
+    pub ident: Ident,
+    pub fields: UnpackedFields,
+    /// The string form of the variant, if the enum uses string values
+    pub value: Option<LitStr>,
+    /// The number set with `#[nest::tag(n)]`
+    pub tag: Option<LitInt>,
+    /// The attributes the variant is emitted with
+    pub attrs: Vec<Attribute>,
+}
+
+/// A definition written inline as the type of a field
+pub(crate) struct InlineDefinition {
+    /// The type of the definition, including the generics from a `FishHook`
+    pub ty: Type,
+    pub kind: DefinitionKind,
+    pub directives: Directives,
+}
+
+/// The fields of a struct or enum variant after unpacking
+pub(crate) enum UnpackedFields {
+    Named(Vec<UnpackedField>),
+    Unnamed(Vec<UnpackedField>),
+    Unit,
+}
+
+impl Unpack for Special {
+    type Output = TokenStream;
+
+    /// Performs unpacking for `Special` structures, handling struct and enum definitions
+    /// uniquely based on their form and attributes.
+    ///
+    /// This function combines current and inherited attributes, applies any context-specific
+    /// modifications, and generates a `TokenStream` representing the Rust code structure of
+    /// the unpacked `Special` instance.
+    ///
+    /// # Parameters
+    /// - `self`: The `Special` instance to be unpacked.
+    /// - `unpack_context`: The context that may influence how unpacking is performed, including
+    ///   attribute modifications.
+    /// - `Next`: Additional attributes that may come from higher-level structures or previous
+    ///   unpacking stages, to be considered in the current unpacking process.
+    ///
+    /// # Returns
+    /// A `TokenStream` representing the generated Rust code after unpacking.
+    fn unpack(self, mut unpack_context: UnpackContext, next: Vec<CompositeAttribute>, override_public: Option<Visibility>, _enum_context: bool) -> Self::Output {
+        // twins of this definition are unpacked in the context of its parent
+        let mut parent_context = unpack_context.clone();
+        let original_attrs = [self.attrs.clone(), next.clone()].concat();
+        // combine the attributes from the current and previous
+        let attrs = [self.attrs, next].concat();
+        let attrs = unpack_context.modify_composite(attrs);
+        // split off the `#[nest::...]` attributes
+        let (attrs, mut directives) = Directives::extract(attrs);
+        // `#[nest::typescript]` also declares every definition nested in this one
+        let typescript_root = directives.typescript && unpack_context.typescript.is_none();
+        if typescript_root {
+            unpack_context.typescript = Some(Default::default());
+        }
+        directives.typescript = unpack_context.typescript.is_some();
+        // so does `#[nest::json_schema]`, with the schemas under `$defs`
+        #[cfg(feature = "json")]
+        let json_schema_root = directives.json_schema && unpack_context.json_schema.is_none();
+        #[cfg(feature = "json")]
+        {
+            if json_schema_root {
+                unpack_context.json_schema = Some(Default::default());
+            }
+            directives.json_schema = unpack_context.json_schema.is_some();
+        }
+        // so does `#[nest::proto]`, where they are nested in the message of this definition
+        let proto_root = directives.proto && unpack_context.proto.is_none();
+        if proto_root {
+            unpack_context.proto = Some(Default::default());
+        }
+        directives.proto = unpack_context.proto.is_some();
+        // the declarations collected from here on belong to the definitions nested in this one
+        let proto_nested = unpack_context.proto.as_ref().map_or(0, |messages| messages.borrow().len());
+        // `#[nest::shape]` applies to every definition nested in this one
+        unpack_context.shape |= directives.shape;
+        directives.shape = unpack_context.shape;
+
+        let visibility = override_public.unwrap_or_else(|| self.vis);
+        let ident = self.ident; // the definition name/type
+        let generics = self.generics;
+        let where_clause = &generics.where_clause;
+        // `#[nest::partial]` and `#[nest::views]` define twins of this struct, or
+        // of the struct this definition is nested in
+        let (twins, twin_definitions) = Twins::resolve(
+            &ident,
+            &visibility,
+            &generics,
+            original_attrs,
+            &self.body,
+            &directives,
+            std::mem::take(&mut unpack_context.twins),
+        );
+
+        // the fields of this definition are unpacked under its directives
+        unpack_context.directives = directives.clone();
+        unpack_context.owner = Some({
+            let (_, ty_generics, _) = generics.split_for_impl();
+            let turbofish = ty_generics.as_turbofish();
+            parse_quote!(#ident #turbofish)
+        });
+        unpack_context.twins = twins.clone();
+        // based on the type of the Special type [struct | enum | union?]
+        // then determine the expansion
+        let (item, definitions, body) = match self.body {
+            Body::Struct(body_struct) => {
+                // unpack our structure body, also collecting the recursive definitions
+                let (body, definitions, fields) = body_struct.fields.unpack(unpack_context.clone(), Vec::default(), None, false);
+
+                let item = match fields {
+                    // define our current ctx struct
+                    // - define attributes
+                    // - define ident and specify generics
+                    // - our previous definitions are inserted behind the struct
+                    UnpackedFields::Named(_) => quote!(
+                        #(#attrs)*
+                        #visibility struct #ident #generics #where_clause #body
+                    ),
+                    UnpackedFields::Unnamed(_) => quote!(
+                        #(#attrs)*
+                        #visibility struct #ident #generics #body #where_clause;
+                    ),
+                    // no unpacking required here, since there are no types
+                    // in other words, this branch is always a leaf
+                    UnpackedFields::Unit => quote!(
+                        #(#attrs)*
+                        #visibility struct #ident #generics;
+                    ),
+                };
+                (item, definitions, UnpackedBody::Struct(fields))
+            }
+            Body::Enum(body_enum) => {
+                let mut accumulated_definitions = vec![];
+                let mut variants = vec![];
+                let mut unpacked_variants = vec![];
+                for error in discriminant::validate(&attrs, &body_enum.variants) {
+                    error.emit();
+                }
+                // once a variant has a string value, every variant has one
+                let string_values = body_enum
+                    .variants
+                    .iter()
+                    .any(|variant| matches!(variant.discriminant, Some(Discriminant::Str { .. })));
+
+                for variant in body_enum.variants {
+                    let (attrs, next) = UnpackContext::filter_field_nested(variant.attrs); // todo: handle this
+                    let (attrs, tag) = proto::take_tag(attrs, directives.proto);
+                    let mut attrs = directives::reject(attrs, "variants");
+                    let ident = variant.ident;
+                    let (field_body, mut definitions, fields) =
+                        variant.fields.unpack(unpack_context.clone(), next, None, true);
+                    accumulated_definitions.append(&mut definitions);
+
+                    // string values are not valid rust, so they are taken out of the variant
+                    let (discriminant, value) = match variant.discriminant {
+                        Some(Discriminant::Str { value, .. }) => (None, Some(value)),
+                        Some(discriminant) if string_values => {
+                            emit_error!(
+                                discriminant.span(),
+                                "cannot mix string values and integer discriminants"
+                            );
+                            (None, None)
+                        }
+                        // variants without a value use their name
+                        None if string_values => {
+                            (None, Some(LitStr::new(&ident.unraw().to_string(), ident.span())))
+                        }
+                        discriminant => (discriminant, None),
+                    };
+
+                    if let Some(value) = &value {
+                        if !matches!(fields, UnpackedFields::Unit) {
+                            emit_error!(
+                                value.span(),
+                                "string values are only supported on unit variants"
+                            );
+                        }
+                        if directives.serde && ident.unraw() != value.value() {
+                            attrs.push(Attribute::new(parse_quote!(serde(rename = #value))));
+                        }
+                    }
+
+                    let variant = quote!(
+                        #(#attrs)*
+                        #ident #field_body
+                        #discriminant
+                    );
+                    variants.push(variant);
+                    unpacked_variants.push(UnpackedVariant { ident, fields, value, tag, attrs });
+                }
+
+                let item = quote!(
+                    #(#attrs)*
+                    #visibility enum #ident #generics #where_clause {
+                        #( #variants ),*
+                    }
+                );
+                (item, accumulated_definitions, UnpackedBody::Enum(unpacked_variants))
+            }
+        };
+
+        let kind = match &body {
+            UnpackedBody::Struct(UnpackedFields::Named(_)) => DefinitionKind::NamedStruct,
+            UnpackedBody::Struct(UnpackedFields::Unnamed(_)) => DefinitionKind::TupleStruct,
+            UnpackedBody::Struct(UnpackedFields::Unit) => DefinitionKind::UnitStruct,
+            UnpackedBody::Enum(_) => DefinitionKind::Enum,
+        };
+
+        // every definition in the invocation is registered so that clashing names can be
+        // reported on both definitions, instead of on the generated code
+        let item = if unpack_context.register_definition(&ident, item.to_string(), kind, &directives) {
+            let definition = Definition {
+                attrs: &attrs,
+                ident: &ident,
+                vis: &visibility,
+                generics: &generics,
+                directives: &directives,
+                twins: &twins,
+                body: &body,
+            };
+            // items generated from the definition, such as trait implementations
+            let generated = generate::generate(&definition);
+            // TypeScript declarations also need the declarations of the nested definitions
+            let declarations = unpack_context.typescript.as_ref().map(|declarations| {
+                generate::typescript::generate(&definition, declarations, typescript_root)
+            });
+
+            #[cfg(feature = "json")]
+            let schema = unpack_context.json_schema.as_ref().map(|schemas| {
+                generate::json_schema::generate(&definition, schemas, json_schema_root)
+            });
+            #[cfg(not(feature = "json"))]
+            let schema = TokenStream::new();
+
+            let proto = unpack_context.proto.as_ref().map(|messages| {
+                generate::proto::generate(&definition, messages, proto_nested, proto_root)
+            });
+
+            quote!(#item #generated #declarations #schema #proto)
+        } else {
+            TokenStream::default()
+        };
+
+        // twins are unpacked like siblings of this definition
+        parent_context.twins = Twins::default();
+        let twins = twin_definitions
+            .into_iter()
+            .map(|twin| twin.unpack(parent_context.clone(), Vec::default(), None, false));
+
+        quote!(
+            #item
+
+            #(#definitions)*
+
+            #(#twins)*
+        )
+    }
+}
+
+impl Unpack for SpecialFields {
+    type Output = (TokenStream, Vec<TokenStream>, UnpackedFields);
+    //             ^body        ^definitions      ^fields
+    fn unpack(self, unpack_context: UnpackContext, next: Vec<CompositeAttribute>, _override_public: Option<Visibility>, enum_context: bool) -> Self::Output {
+        match self {
+            // Delegates to the `unpack` implementation of `FieldsNamed`, which handles the
+            // unpacking of named fields,
+            // including generating the necessary code and collecting
+            // any additional definitions.
+            SpecialFields::Named(named) => {
+                let (body, definitions, fields) = named.unpack(unpack_context, next, None, enum_context);
+                (body, definitions, UnpackedFields::Named(fields))
+            }
+
+            // Similarly, for unnamed fields (tuples), it delegates to `FieldsUnnamed`'s
+            // `unpack` method, which is specialized in handling tuple-like structures.
+            SpecialFields::Unnamed(unnamed) => {
+                let (body, definitions, fields) = unnamed.unpack(unpack_context, next, None, enum_context);
+                (body, definitions, UnpackedFields::Unnamed(fields))
+            }
+
+            // For unit types, which have no fields, the function returns a default (empty)
+            // `TokenStream` along with an empty vector for definitions,
+            // as there's no additional
+            // code needed to represent a unit type in Rust.
+            SpecialFields::Unit => (TokenStream::default(), Vec::<TokenStream>::default(), UnpackedFields::Unit),
+        }
+    }
+}
+
+impl Unpack for FieldsNamed {
+    type Output = (TokenStream, Vec<TokenStream>, Vec<UnpackedField>);
+    //             ^body        ^definitions      ^fields
+    fn unpack(self, unpack_context: UnpackContext, from_variant: Vec<CompositeAttribute>, _override_public: Option<Visibility>, enum_context: bool) -> Self::Output {
+        // fields buffer load each
+        let mut fields = vec![];
+        let mut definitions = vec![];
+        let mut unpacked = vec![];
+
+        // iterate through the fields
+        for field in splice_spread_fields(self.named, unpack_context.directives.serde) {
+            // the twins of a struct defined as the type of this field
+            let twins = unpack_context.twins.nested(&field);
+
+            // filter the attributes, passing the #> to the next iteration,
+            // we need to filter the attributes so that we can determine which are normal
+            // or which should be passed on
+            let (attrs, next) = UnpackContext::filter_field_nested(field.attrs);
+
+            // `#[nest::only(...)]` assigns the field to views
+            let (only, attrs): (Vec<_>, Vec<_>) = attrs.into_iter().partition(|attr| views::is_only(&attr.meta));
+            only.iter().for_each(|attr| views::check_only(&attr.meta, &unpack_context.twins.views));
+            let only = only.into_iter().flat_map(|attr| views::parse_only(&attr.meta).unwrap_or_default()).collect();
+            // `#[nest::tag(n)]` sets the Protobuf field number
+            let (attrs, tag) = proto::take_tag(attrs, unpack_context.directives.proto);
+            let attrs = directives::reject(attrs, "named fields");
+            let vis = field.vis;
+            // unused field mutability see syn doc for FieldMutability
+            let _mutability = field.mutability;
+            // this is a named type, so there should always be an ident
+            // if there is no ident then there should be a parsing bug
+            let ident = field.ident.unwrap_or_else(|| {
+                panic!(
+                    "Internal Macro Error. This is a bug. \
+                Please Consider opening an issue with steps to reproduce the bug \
+                Provide this information: Error from line {}",
+                    { line!() }
+                )
+            });
+
+            let fish = field.fish;
+
+            // branch off the type depending on if leaf is reached
+            let (ty, inline) = match field.ty {
+                // leaf node aka a non-special type => don't recurse
+                // `SpecialType::Type`
+                // doesn't need fish because it will always be None
+                SpecialType::Type(ty) => (ty, None),
+                SpecialType::Augmented(augmented) => {
+                    // combine attributes possibly inherited from an enum variant with field attrs
+                    let next = [next, from_variant.clone()].concat();
+
+                    let optional_definition = augmented.optional_definition();
+
+                    let mut unpack_context = unpack_context.clone();
+                    // definitions inside of types are reused as they are by twins
+                    unpack_context.twins = Twins::default();
+
+                    let (ty, mut aug_definitions) = augmented.unpack(unpack_context.clone(), next, None, enum_context);
+                    definitions.append(&mut aug_definitions);
+
+                    let inline = optional_definition
+                        .and_then(|(ident, ty)| unpack_context.inline_definition(&ident, ty));
+                    (ty, inline)
+                }
+                // recuse down the parse stack
+                SpecialType::Def(special) => {
+                    // trust that ty will be a definition step
+                    let ty = type_from_ident_and_fish(special.ident.clone(), fish);
+                    let def_ident = special.ident.clone();
+
+                    // combine attributes possibly inherited from an enum variant with field attrs
+                    let next = [next, from_variant.clone()].concat();
+
+                    let mut unpack_context = unpack_context.clone();
+                    unpack_context.twins = twins;
+
+                    // unpack the definition of the type
+                    // then add it to the definition buffer
+                    // this could be one or more definition
+                    // we don't care
+                    let definition = special.unpack(unpack_context.clone(), next, None, enum_context);
+                    definitions.push(definition);
+
+                    let inline = unpack_context.inline_definition(&def_ident, ty.clone());
+                    (ty, inline)
+                }
+            };
+
+            let mut attrs = attrs;
+            let serde = unpack_context.directives.serde;
+
+            // in serde mode, `..struct Name { .. }` is embedded through `flatten`
+            let flatten = field.spread.is_some();
+            if flatten {
+                attrs.push(Attribute::new(parse_quote!(serde(flatten))));
+            }
+
+            // fields named with a string literal keep their original name when serialized
+            if let Some(rename) = field.rename.filter(|rename| ident.unraw() != rename.value()) {
+                attrs.push(Attribute::new(parse_quote!(serde(rename = #rename))));
+            }
+
+            // `field: T = value` is generated into a `Default` implementation
+            let default = field.default.map(|(eq_token, value)| {
+                if enum_context {
+                    emit_error!(
+                        eq_token.span(),
+                        "default values are only supported on struct fields"
+                    );
+                } else if let (true, Some(owner)) = (serde, &unpack_context.owner) {
+                    let helper = format_ident!("default_{}", ident.unraw());
+                    let helper = quote!(#owner::#helper).to_string();
+                    attrs.push(Attribute::new(parse_quote!(serde(default = #helper))));
+                }
+                value
+            });
+
+            // `field?: T` is shorthand for `field: Option<T>`
+            let ty = if field.question_token.is_some() {
+                if serde && default.is_some() {
+                    attrs.push(Attribute::new(parse_quote!(
+                        serde(skip_serializing_if = "Option::is_none")
+                    )));
+                } else if serde {
+                    attrs.push(Attribute::new(parse_quote!(
+                        serde(default, skip_serializing_if = "Option::is_none")
+                    )));
+                }
+                parse_quote!(::core::option::Option<#ty>)
+            } else {
+                ty
+            };
+
+            let field = quote!(
+                #(#attrs)*
+                #vis #ident : #ty
+            );
+            fields.push(field);
+            unpacked.push(UnpackedField {
+                ident: Some(ident),
+                optional: is_option(&ty),
+                ty,
+                default,
+                inline,
+                flatten,
+                only,
+                tag,
+                attrs,
+            });
+        }
+
+        let body = quote!(
+            { #(#fields),* }
+        );
+
+        (body, definitions, unpacked)
+    }
+}
+
+impl Unpack for FieldsUnnamed {
+    type Output = (TokenStream, Vec<TokenStream>, Vec<UnpackedField>);
+    //             ^body        ^definitions      ^fields
+    fn unpack(self, unpack_context: UnpackContext, from_variant: Vec<CompositeAttribute>, _override_public: Option<Visibility>, enum_context: bool) -> Self::Output {
+        let mut fields = vec![];
+        let mut definitions = vec![];
+        let mut unpacked = vec![];
+
+        // iterate through types
+        for field in self.unnamed {
+            // filter the attributes, passing the #> to the next iteration
+            let (attrs, next) = UnpackContext::filter_field_nested(field.attrs);
+            let (attrs, tag) = proto::take_tag(attrs, unpack_context.directives.proto);
+            let attrs = directives::reject(attrs, "tuple fields");
+
+            // let vis = field.vis;
+            // if we are in an enum variant then don't show the visibility to the field
+            let move_vis = field.vis;
+            let vis = if enum_context {
+                None
+            } else {
+                Some(&move_vis)
+            };
+
+            // unused field mutability see syn doc for FieldMutability
+            let _mutability = field.mutability;
+
+            // this is an unnamed variant so there should never Some(T)
+            let _ident = field.ident; // todo: warn if this is not none
+
+            let fish = field.fish;
+
+
+            // branch off based on if a type is defined or should be defined
+            let (ty, inline) = match field.ty {
+                SpecialType::Type(ty) => (ty, None),
+                SpecialType::Augmented(augmented) => {
+                    // combine attributes possibly inherited from an enum variant with field attrs
+                    let next = [next, from_variant.clone()].concat();
+
+                    // if it is an unnamed field, then the definition visibility must be overridden
+                    let override_publicity = Some(move_vis.clone());
+
+                    // if field is unnamed the field publicity should be applied to the definition
+
+                    let optional_definition = augmented.optional_definition();
+
+                    let (ty, mut aug_definitions) = augmented.unpack(unpack_context.clone(), next, override_publicity, enum_context);
+                    definitions.append(&mut aug_definitions);
+
+                    let inline = optional_definition
+                        .and_then(|(ident, ty)| unpack_context.inline_definition(&ident, ty));
+                    (ty, inline)
+                }
+                SpecialType::Def(special) => {
+                    let ty = type_from_ident_and_fish(special.ident.clone(), fish);
+                    let def_ident = special.ident.clone();
+
+                    // combine attributes possibly inherited from an enum variant with field attrs
+                    let next = [next, from_variant.clone()].concat();
+
+                    // if it is an unnamed field, then the definition visibility must be overridden
+                    let override_publicity = Some(move_vis.clone());
+
+                    // if field is unnamed the field publicity should be applied to the definition
+
+                    let definition = special.unpack(unpack_context.clone(), next, override_publicity, enum_context);
+                    definitions.push(definition);
+
+                    let inline = unpack_context.inline_definition(&def_ident, ty.clone());
+                    (ty, inline)
+                }
+            };
+
+            // `T = value` is generated into a `Default` implementation
+            let default = field.default.map(|(eq_token, value)| {
+                if enum_context {
+                    emit_error!(
+                        eq_token.span(),
+                        "default values are only supported on struct fields"
+                    );
+                }
+                value
+            });
+
+            let field = quote!(
+                #(#attrs)*
+                #vis #ty
+            );
+            fields.push(field);
+            unpacked.push(UnpackedField {
+                ident: None,
+                optional: is_option(&ty),
+                ty,
+                default,
+                inline,
+                flatten: false,
+                only: vec![],
+                tag,
+                attrs,
+            });
+        }
+
+        let body = quote!(
+            ( #(#fields),* )
+        );
+
+        (body, definitions, unpacked)
+    }
+}
+
+/// Without serde mode, spread fields `..struct Name { .. }` are replaced by the fields
+/// of the struct, as if they were written in the parent
+fn splice_spread_fields(fields: impl IntoIterator<Item = SpecialField>, serde: bool) -> Vec<SpecialField> {
+    let mut spliced = vec![];
+
+    for field in fields {
+        match field.ty {
+            SpecialType::Def(Special {
+                body: Body::Struct(BodyStruct { fields: SpecialFields::Named(named), .. }),
+                ..
+            }) if field.spread.is_some() && !serde => {
+                spliced.extend(splice_spread_fields(named.named, serde));
+            }
+            ty => spliced.push(SpecialField { ty, ..field }),
+        }
+    }
+
+    spliced
+}
+
+/// Returns `true` if the type is an `Option<_>`
+pub(crate) fn is_option(ty: &Type) -> bool {
+    option_inner(ty).is_some()
+}
+
+/// Returns `T` if the type is an `Option<T>`
+pub(crate) fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+
+    let segment = path.path.segments.last()?;
+    let syn::PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+
+    match arguments.args.first() {
+        Some(syn::GenericArgument::Type(inner))
+            if path.qself.is_none() && segment.ident == "Option" && arguments.args.len() == 1 =>
+        {
+            Some(inner)
+        }
+        _ => None,
+    }
+}
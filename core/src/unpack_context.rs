@@ -1,13 +1,41 @@
 use crate::attribute_removal::remove_or_subtract_attr;
 use crate::attributes::{Attribute, AttributeModifier, CompositeAttribute, FieldAttribute};
+use crate::directives::Directives;
+use crate::registry::{DefinitionKind, Registration, Registry};
+use crate::twin::Twins;
+use crate::unpack::InlineDefinition;
+use proc_macro_error::emit_error;
+use std::cell::RefCell;
+use std::rc::Rc;
+use syn::{Ident, Path, Type};
 
 #[derive(Clone, Default)]
 pub(crate) struct UnpackContext {
     inherited: Vec<Attribute>,
+    registry: Rc<RefCell<Registry>>,
+    /// The directives of the definition whose fields are currently being unpacked
+    pub(crate) directives: Directives,
+    /// The path of the definition whose fields are currently being unpacked,
+    /// with its generic parameters
+    pub(crate) owner: Option<Path>,
+    /// The twins of the struct whose fields are currently being unpacked
+    pub(crate) twins: Twins,
+    /// The TypeScript declarations of the definitions nested in a definition with
+    /// `#[nest::typescript]`, collected for the declarations of the whole tree
+    pub(crate) typescript: Option<Rc<RefCell<Vec<String>>>>,
+    /// The JSON Schemas of the definitions nested in a definition with `#[nest::json_schema]`,
+    /// collected for its `$defs`
+    #[cfg(feature = "json")]
+    pub(crate) json_schema: Option<Rc<RefCell<serde_json::Map<String, serde_json::Value>>>>,
+    /// The Protobuf declarations of the definitions nested in a definition with `#[nest::proto]`,
+    /// until the definition they are nested in takes them
+    pub(crate) proto: Option<Rc<RefCell<Vec<String>>>>,
+    /// Inside a definition with `#[nest::shape]`, which every nested definition also gets
+    pub(crate) shape: bool,
 }
 
 impl UnpackContext {
-    pub fn modify_composite(&mut self, attributes: Vec<CompositeAttribute>) -> Vec<Attribute> {
+    pub(crate) fn modify_composite(&mut self, attributes: Vec<CompositeAttribute>) -> Vec<Attribute> {
         let mut freeze = self.inherited.clone();
 
         let current = attributes
@@ -92,4 +120,46 @@ impl UnpackContext {
 
         (field_applied_now, composite)
     }
+
+    /// Records an expanded definition for the whole invocation.
+    ///
+    /// Returns `false` if the definition should not be emitted, because an identical one
+    /// already was or because its name is already taken, in which case both definitions
+    /// are reported.
+    pub(crate) fn register_definition(
+        &self,
+        ident: &Ident,
+        key: String,
+        kind: DefinitionKind,
+        directives: &Directives,
+    ) -> bool {
+        match self.registry.borrow_mut().register(ident, key, kind, directives) {
+            Registration::Fresh => true,
+            Registration::Merged => false,
+            Registration::Conflict(first) => {
+                emit_error!(
+                    first,
+                    "`{}` is defined multiple times in this `nest!` invocation", ident;
+                    note = "first defined here"
+                );
+                emit_error!(
+                    ident.span(),
+                    "`{}` is defined multiple times in this `nest!` invocation", ident;
+                    help = "rename one of the definitions, or if they are identical, \
+                    merge them with `#[nest::dedup]*` on the outermost definition"
+                );
+                false
+            }
+        }
+    }
+
+    /// Looks up a definition that has already been expanded,
+    /// to describe it as the inline type `ty` of a field
+    pub(crate) fn inline_definition(&self, ident: &Ident, ty: Type) -> Option<InlineDefinition> {
+        self.registry.borrow().get(ident).map(|entry| InlineDefinition {
+            ty,
+            kind: entry.kind,
+            directives: entry.directives.clone(),
+        })
+    }
 }
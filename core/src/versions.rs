@@ -0,0 +1,550 @@
+//! Versioned types, defined with `#[nest::versions(v1, v2, v3)]` on the outermost definition.
+//!
+//! Every version is a module with a copy of the whole tree, named like the original. Fields and
+//! variants marked with `#[nest::since(v2)]` are only part of `v2` and later versions, the ones
+//! marked with `#[nest::until(v2)]` are only part of the versions before `v2`.
+//!
+//! A definition gets a `From` upgrade to the next version when that version only adds fields,
+//! and every added field has a default value or is optional. Definitions nested in options,
+//! boxes, collections and maps are upgraded element by element.
+
+use crate::attributes::FieldAttribute;
+use crate::directives::is_directive;
+use crate::fish::FishHook;
+use crate::special_data::{Body, BodyStruct, Special, SpecialField, SpecialFields};
+use crate::ty::{augmented, SpecialType};
+use crate::unpack::Unpack;
+use crate::unpack_context::UnpackContext;
+use proc_macro2::{TokenStream, TokenTree};
+use proc_macro_error::emit_error;
+use quote::{format_ident, quote, ToTokens};
+use std::ops::Range;
+use syn::punctuated::Punctuated;
+use syn::{parse_quote, Ident, Index, Meta, Token, Visibility};
+
+/// Expands the versions of `root` if it has `#[nest::versions(...)]`, and returns them with the
+/// names of the versions. Otherwise, returns `None` after reporting `#[nest::since]` and
+/// `#[nest::until]`, which only apply to versioned types.
+pub(crate) fn expand(root: &mut Special) -> Option<(TokenStream, Vec<Ident>)> {
+    let versions = take_versions(root);
+    check_markers(root, versions.as_deref());
+
+    let Some(versions) = versions else {
+        root.visit(&mut |special| filter(special, None));
+        return None;
+    };
+
+    let mut names = vec![];
+    root.visit(&mut |special| names.push(special.ident.clone()));
+
+    let upgrades = versions.windows(2).enumerate().flat_map(|(from, pair)| {
+        let mut upgrade = Upgrade {
+            versions: &versions,
+            names: &names,
+            from,
+            impls: vec![],
+        };
+        upgrade.definition(root);
+
+        let (from, into) = (&pair[0], &pair[1]);
+        upgrade.impls.into_iter().map(move |(ident, generics, body)| {
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+            quote!(
+                impl #impl_generics ::core::convert::From<#from::#ident #ty_generics> for #into::#ident #ty_generics #where_clause {
+                    #[allow(unused_variables)]
+                    fn from(value: #from::#ident #ty_generics) -> Self {
+                        #body
+                    }
+                }
+            )
+        })
+    });
+    let upgrades: Vec<TokenStream> = upgrades.collect();
+
+    let vis = &root.vis;
+    let modules = versions.iter().enumerate().map(|(index, version)| {
+        let mut tree = root.clone();
+        tree.visit(&mut |special| filter(special, Some((index, &versions))));
+        let tree = tree.unpack(UnpackContext::default(), Vec::default(), None, false);
+
+        quote!(
+            #vis mod #version {
+                #[allow(unused_imports)]
+                use super::*;
+
+                #tree
+            }
+        )
+    });
+
+    let expansion = quote!(
+        #(#modules)*
+
+        #(#upgrades)*
+    );
+    Some((expansion, versions))
+}
+
+/// Removes `#[nest::versions(...)]` from the outermost definition and returns the versions
+fn take_versions(root: &mut Special) -> Option<Vec<Ident>> {
+    let index = root.attrs.iter().position(|attr| {
+        let path = attr.meta.path();
+        is_directive(path) && path.segments[1].ident == "versions"
+    })?;
+    let attr = root.attrs.remove(index);
+
+    let versions: Vec<Ident> = match attr
+        .meta
+        .require_list()
+        .and_then(|list| list.parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated))
+    {
+        Ok(versions) if !versions.is_empty() => versions.into_iter().collect(),
+        _ => {
+            emit_error!(
+                attr.meta, "expected the names of the versions";
+                help = "for example `#[nest::versions(v1, v2)]`"
+            );
+            return None;
+        }
+    };
+
+    for (index, version) in versions.iter().enumerate() {
+        if versions[..index].contains(version) {
+            emit_error!(version, "version `{}` is listed more than once", version);
+        }
+    }
+
+    Some(versions)
+}
+
+/// Returns `true` for `#[nest::since(...)]` and `#[nest::until(...)]`
+fn is_marker(meta: &Meta) -> bool {
+    let path = meta.path();
+    is_directive(path) && (path.segments[1].ident == "since" || path.segments[1].ident == "until")
+}
+
+/// The versions a field or variant is part of, as indices into `versions`.
+/// Malformed markers are reported by [`check_markers`].
+fn range(attrs: &[FieldAttribute], versions: &[Ident]) -> Range<usize> {
+    let mut range = 0..versions.len();
+
+    for attr in attrs {
+        let FieldAttribute::Field(attr) = attr else {
+            continue;
+        };
+        if !is_marker(&attr.meta) {
+            continue;
+        }
+        let Some(index) = parse_marker(&attr.meta)
+            .ok()
+            .and_then(|version| versions.iter().position(|v| *v == version))
+        else {
+            continue;
+        };
+
+        if attr.meta.path().segments[1].ident == "since" {
+            range.start = index;
+        } else {
+            range.end = index;
+        }
+    }
+
+    range
+}
+
+fn parse_marker(meta: &Meta) -> syn::Result<Ident> {
+    meta.require_list()?.parse_args::<Ident>()
+}
+
+/// Reports markers that are malformed, name unknown versions, or are used without versions
+fn check_markers(root: &mut Special, versions: Option<&[Ident]>) {
+    root.visit(&mut |special| {
+        for attrs in attr_lists(special) {
+            let mut since = None;
+
+            for attr in attrs {
+                let FieldAttribute::Field(attr) = attr else {
+                    continue;
+                };
+                if !is_marker(&attr.meta) {
+                    continue;
+                }
+                let name = &attr.meta.path().segments[1].ident;
+
+                let Some(versions) = versions else {
+                    emit_error!(
+                        attr.meta, "`#[nest::{}]` requires `#[nest::versions(...)]` on the outermost definition", name;
+                        help = "for example `#[nest::versions(v1, v2)]`"
+                    );
+                    continue;
+                };
+
+                let version = match parse_marker(&attr.meta) {
+                    Ok(version) => version,
+                    Err(_) => {
+                        emit_error!(
+                            attr.meta, "expected the name of a version";
+                            help = "for example `#[nest::{}(v2)]`", name
+                        );
+                        continue;
+                    }
+                };
+
+                let Some(index) = versions.iter().position(|v| *v == version) else {
+                    emit_error!(
+                        version, "`{}` is not a version of this type", version;
+                        help = "the versions are: {}", versions
+                            .iter()
+                            .map(|version| format!("`{version}`"))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    );
+                    continue;
+                };
+
+                if name == "since" {
+                    since = Some(index);
+                } else if since.is_some_and(|since| since >= index) || index == 0 {
+                    emit_error!(
+                        version, "`#[nest::until({})]` leaves no version to be part of", version;
+                        note = "`until` is exclusive, the field or variant is only part of the versions before `{}`", version
+                    );
+                }
+            }
+        }
+    });
+}
+
+/// The attributes of every field and variant directly in a definition
+fn attr_lists(special: &Special) -> Vec<&[FieldAttribute]> {
+    match &special.body {
+        Body::Struct(BodyStruct { fields, .. }) => fields_of(fields).map(|field| field.attrs.as_slice()).collect(),
+        Body::Enum(body) => body
+            .variants
+            .iter()
+            .flat_map(|variant| {
+                std::iter::once(variant.attrs.as_slice())
+                    .chain(fields_of(&variant.fields).map(|field| field.attrs.as_slice()))
+            })
+            .collect(),
+    }
+}
+
+fn fields_of(fields: &SpecialFields) -> impl Iterator<Item = &SpecialField> {
+    match fields {
+        SpecialFields::Named(named) => Some(named.named.iter()),
+        SpecialFields::Unnamed(unnamed) => Some(unnamed.unnamed.iter()),
+        SpecialFields::Unit => None,
+    }
+    .into_iter()
+    .flatten()
+}
+
+/// Removes the fields and variants of a definition that are not part of the version at `index`,
+/// or only removes the markers with `None`.
+///
+/// Private definitions and fields are made visible to the parent module, where the
+/// upgrades between versions are implemented.
+fn filter(special: &mut Special, version: Option<(usize, &[Ident])>) {
+    let present = |attrs: &[FieldAttribute]| match version {
+        Some((index, versions)) => range(attrs, versions).contains(&index),
+        None => true,
+    };
+    let expose = |vis: &mut Visibility| {
+        if version.is_some() && matches!(vis, Visibility::Inherited) {
+            *vis = parse_quote!(pub(super));
+        }
+    };
+
+    expose(&mut special.vis);
+
+    match &mut special.body {
+        Body::Struct(BodyStruct { fields, .. }) => {
+            filter_fields(fields, &present);
+            match fields {
+                SpecialFields::Named(named) => named.named.iter_mut().for_each(|field| expose(&mut field.vis)),
+                SpecialFields::Unnamed(unnamed) => unnamed.unnamed.iter_mut().for_each(|field| expose(&mut field.vis)),
+                SpecialFields::Unit => {}
+            }
+        }
+        Body::Enum(body) => {
+            body.variants = std::mem::take(&mut body.variants)
+                .into_iter()
+                .filter(|variant| present(&variant.attrs))
+                .map(|mut variant| {
+                    strip_markers(&mut variant.attrs);
+                    filter_fields(&mut variant.fields, &present);
+                    // the visibility of definitions in tuple variants comes from their field
+                    if let SpecialFields::Unnamed(unnamed) = &mut variant.fields {
+                        unnamed.unnamed.iter_mut().for_each(|field| expose(&mut field.vis));
+                    }
+                    variant
+                })
+                .collect();
+        }
+    }
+}
+
+fn filter_fields(fields: &mut SpecialFields, present: &impl Fn(&[FieldAttribute]) -> bool) {
+    let fields = match fields {
+        SpecialFields::Named(named) => &mut named.named,
+        SpecialFields::Unnamed(unnamed) => &mut unnamed.unnamed,
+        SpecialFields::Unit => return,
+    };
+
+    *fields = std::mem::take(fields)
+        .into_iter()
+        .filter(|field| present(&field.attrs))
+        .map(|mut field| {
+            strip_markers(&mut field.attrs);
+            field
+        })
+        .collect();
+}
+
+fn strip_markers(attrs: &mut Vec<FieldAttribute>) {
+    attrs.retain(|attr| !matches!(attr, FieldAttribute::Field(attr) if is_marker(&attr.meta)));
+}
+
+/// Collects the upgrades of the definitions in a tree, from the version at `from` to the next
+struct Upgrade<'a> {
+    versions: &'a [Ident],
+    /// The names of every definition in the tree
+    names: &'a [Ident],
+    from: usize,
+    /// The name, generics and body of `From::from` of every upgraded definition
+    impls: Vec<(Ident, syn::Generics, TokenStream)>,
+}
+
+impl Upgrade<'_> {
+    /// Whether a field or variant is part of the version being upgraded and the next one
+    fn present(&self, attrs: &[FieldAttribute]) -> (bool, bool) {
+        let range = range(attrs, self.versions);
+        (range.contains(&self.from), range.contains(&(self.from + 1)))
+    }
+
+    /// Collects the upgrade of `special` and the definitions nested in it, returns `true`
+    /// if `special` can be upgraded
+    fn definition(&mut self, special: &Special) -> bool {
+        let from = &self.versions[self.from];
+        let ident = &special.ident;
+
+        let body = match &special.body {
+            Body::Struct(BodyStruct { fields, .. }) => {
+                self.fields(fields, |member| quote!(value.#member)).map(|fields| quote!(Self #fields))
+            }
+            Body::Enum(body) => {
+                let mut arms = vec![];
+                let mut upgradable = true;
+
+                for variant in &body.variants {
+                    let variant_ident = &variant.ident;
+                    match self.present(&variant.attrs) {
+                        (true, true) => {}
+                        // variants may only be added
+                        (true, false) => upgradable = false,
+                        (false, _) => continue,
+                    }
+
+                    let pattern = match &variant.fields {
+                        SpecialFields::Named(named) => {
+                            let bindings = named
+                                .named
+                                .iter()
+                                .filter(|field| self.present(&field.attrs).0)
+                                .map(|field| &field.ident);
+                            quote!({ #(#bindings),* })
+                        }
+                        SpecialFields::Unnamed(unnamed) => {
+                            let bindings = unnamed
+                                .unnamed
+                                .iter()
+                                .filter(|field| self.present(&field.attrs).0)
+                                .enumerate()
+                                .map(|(index, _)| format_ident!("field_{}", index));
+                            quote!(( #(#bindings),* ))
+                        }
+                        SpecialFields::Unit => TokenStream::default(),
+                    };
+
+                    let fields = self.fields(&variant.fields, |member| match member {
+                        syn::Member::Named(ident) => quote!(#ident),
+                        syn::Member::Unnamed(index) => {
+                            let binding = format_ident!("field_{}", index);
+                            quote!(#binding)
+                        }
+                    });
+                    match fields {
+                        Some(fields) => arms.push(quote!(
+                            #from::#ident::#variant_ident #pattern => Self::#variant_ident #fields
+                        )),
+                        None => upgradable = false,
+                    }
+                }
+
+                upgradable.then(|| quote!(match value { #(#arms),* }))
+            }
+        };
+
+        match body {
+            Some(body) => {
+                self.impls.push((ident.clone(), special.generics.clone(), body));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The fields of the next version built from `value(member)`, with the delimiters,
+    /// or `None` if fields were removed or can't be converted
+    fn fields(&mut self, fields: &SpecialFields, value: impl Fn(syn::Member) -> TokenStream) -> Option<TokenStream> {
+        let mut upgradable = true;
+        let mut values = vec![];
+
+        // the position of tuple fields in the version being upgraded
+        let mut index = 0;
+
+        for field in fields_of(fields) {
+            let member = match &field.ident {
+                Some(ident) => syn::Member::Named(ident.clone()),
+                None => syn::Member::Unnamed(Index::from(index)),
+            };
+            let present = self.present(&field.attrs);
+            if present.0 {
+                index += 1;
+            }
+
+            let converted = match present {
+                // the definitions nested in the field are upgraded even if this one can't be
+                (true, true) => self.convert(field, value(member.clone())),
+                (true, false) => None,
+                (false, true) => match (&field.default, field.question_token, &member) {
+                    // tuple fields can't be added without moving the ones after them
+                    (_, _, syn::Member::Unnamed(_)) => None,
+                    (Some((_, default)), _, _) => Some(quote!(#default)),
+                    (None, Some(_), _) => Some(quote!(::core::option::Option::None)),
+                    (None, None, _) => None,
+                },
+                (false, false) => continue,
+            };
+
+            match converted {
+                Some(converted) => values.push((member, converted)),
+                None => upgradable = false,
+            }
+        }
+
+        if !upgradable {
+            return None;
+        }
+
+        Some(match fields {
+            SpecialFields::Named(_) => {
+                let (members, values): (Vec<_>, Vec<_>) = values.into_iter().unzip();
+                quote!({ #(#members: #values),* })
+            }
+            SpecialFields::Unnamed(_) => {
+                let values = values.into_iter().map(|(_, value)| value);
+                quote!(( #(#values),* ))
+            }
+            SpecialFields::Unit => TokenStream::default(),
+        })
+    }
+
+    /// Converts the value of a field that is part of both versions
+    fn convert(&mut self, field: &SpecialField, value: TokenStream) -> Option<TokenStream> {
+        // the embedded struct is a different struct in every version
+        if field.spread.is_some() {
+            return None;
+        }
+
+        match field.question_token {
+            Some(_) => {
+                let converted = self.value(&field.ty, &field.fish, quote!(value))?;
+                Some(quote!(#value.map(|value| #converted)))
+            }
+            None => self.value(&field.ty, &field.fish, value),
+        }
+    }
+
+    /// Converts `value` to `ty` of the next version, collecting the upgrades of the
+    /// definitions nested in `ty`
+    fn value(&mut self, ty: &SpecialType, fish: &Option<FishHook>, value: TokenStream) -> Option<TokenStream> {
+        match ty {
+            SpecialType::Def(special) => self
+                .definition(special)
+                .then(|| quote!(::core::convert::Into::into(#value))),
+            SpecialType::Augmented(ty) if has_definitions(ty) => self.generic(ty, value),
+            // a type that names a definition in the tree is a different type in every version
+            ty => (!mentions(ty.reference(fish).into_token_stream(), self.names)).then_some(value),
+        }
+    }
+
+    /// Converts `value` of a generic type with definitions in its arguments. Options, boxes,
+    /// collections and maps are converted element by element, other types are not converted.
+    fn generic(&mut self, ty: &augmented::Type, value: TokenStream) -> Option<TokenStream> {
+        let augmented::Type::Path(path) = ty;
+        let segment = path.path.segments.last()?;
+        let augmented::PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+            return None;
+        };
+
+        let types: Vec<_> = arguments
+            .args
+            .iter()
+            .filter_map(|argument| match argument {
+                augmented::GenericArgument::Type(ty, fish) => Some((ty, fish)),
+                _ => None,
+            })
+            .collect();
+        let bindings = match types.len() {
+            2 => vec![quote!(key), quote!(value)],
+            _ => vec![quote!(value); types.len()],
+        };
+
+        // the definitions are upgraded even if the type they are nested in can't be converted
+        let converted: Vec<_> = types
+            .into_iter()
+            .zip(bindings)
+            .map(|((ty, fish), binding)| self.value(ty, fish, binding))
+            .collect();
+        let converted: Vec<_> = converted.into_iter().collect::<Option<_>>()?;
+
+        match (segment.ident.to_string().as_str(), converted.as_slice()) {
+            ("Option", [item]) => Some(quote!(#value.map(|value| #item))),
+            ("Box", [item]) => Some(quote!(::std::boxed::Box::new({
+                let value = *#value;
+                #item
+            }))),
+            ("Vec" | "VecDeque" | "LinkedList" | "HashSet" | "BTreeSet" | "BinaryHeap", [item]) => {
+                Some(quote!(#value.into_iter().map(|value| #item).collect()))
+            }
+            ("HashMap" | "BTreeMap", [key, item]) => {
+                Some(quote!(#value.into_iter().map(|(key, value)| (#key, #item)).collect()))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Whether `tokens` contain one of `names`
+fn mentions(tokens: TokenStream, names: &[Ident]) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => names.contains(&ident),
+        TokenTree::Group(group) => mentions(group.stream(), names),
+        _ => false,
+    })
+}
+
+fn has_definitions(ty: &augmented::Type) -> bool {
+    let augmented::Type::Path(path) = ty;
+
+    path.path.segments.iter().any(|segment| match &segment.arguments {
+        augmented::PathArguments::AngleBracketed(arguments) => arguments.args.iter().any(|argument| match argument {
+            augmented::GenericArgument::Type(SpecialType::Def(_), _) => true,
+            augmented::GenericArgument::Type(SpecialType::Augmented(ty), _) => has_definitions(ty),
+            _ => false,
+        }),
+        _ => false,
+    })
+}
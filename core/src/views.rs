@@ -0,0 +1,194 @@
+//! Views of structs, defined with `#[nest::views(request, response)]`.
+//!
+//! Every view is a twin of the struct, with only the fields that belong to it: fields marked
+//! with `#[nest::only(request)]` only belong to the `request` view, every other field belongs
+//! to all views. The view `request` of `User` is named `UserRequest`, and nested structs with
+//! named fields get views of their own, such as `AddressRequest`.
+
+use crate::attributes::{CompositeAttribute, FieldAttribute};
+use crate::case::to_pascal_case;
+use crate::directives::is_directive;
+use crate::special_data::{Body, BodyStruct, Special, SpecialField, SpecialFields};
+use crate::ty::SpecialType;
+use crate::twin::{has_twin, twin_attrs, twin_field_attrs, twin_ident};
+use proc_macro_error::emit_error;
+use syn::ext::IdentExt;
+use syn::punctuated::Punctuated;
+use syn::{FieldMutability, Generics, Ident, Meta, Token, Visibility};
+
+/// The suffix of the twins of a view: `request` -> `Request`
+pub(crate) fn suffix(view: &Ident) -> String {
+    to_pascal_case(&view.unraw().to_string())
+}
+
+/// The views listed in `#[nest::only(...)]` on a field, empty if the field belongs to all views.
+/// Malformed lists are reported by [`check_only`].
+pub(crate) fn only(attrs: &[FieldAttribute]) -> Vec<Ident> {
+    attrs
+        .iter()
+        .filter_map(|attr| match attr {
+            FieldAttribute::Field(attr) if is_only(&attr.meta) => parse_only(&attr.meta).ok(),
+            _ => None,
+        })
+        .flatten()
+        .collect()
+}
+
+pub(crate) fn parse_only(meta: &Meta) -> syn::Result<Vec<Ident>> {
+    let list = meta.require_list()?;
+    let views = list.parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated)?;
+    Ok(views.into_iter().collect())
+}
+
+/// Reports `#[nest::only(...)]` that is malformed or lists views the struct doesn't have
+pub(crate) fn check_only(meta: &Meta, views: &[Ident]) {
+    let only = match parse_only(meta) {
+        Ok(only) if !only.is_empty() => only,
+        _ => {
+            emit_error!(
+                meta, "expected the names of views";
+                help = "for example `#[nest::only(request)]`"
+            );
+            return;
+        }
+    };
+
+    if views.is_empty() {
+        emit_error!(
+            meta, "`#[nest::only]` requires `#[nest::views(...)]` on an enclosing struct";
+            help = "for example `#[nest::views(request, response)]`"
+        );
+        return;
+    }
+
+    for view in only.iter().filter(|view| !views.contains(view)) {
+        emit_error!(
+            view, "`{}` is not a view of this struct", view;
+            help = "the views of this struct are: {}", views
+                .iter()
+                .map(|view| format!("`{view}`"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+}
+
+/// Returns `true` for `#[nest::only(...)]`
+pub(crate) fn is_only(meta: &Meta) -> bool {
+    let path = meta.path();
+    is_directive(path) && path.segments[1].ident == "only"
+}
+
+/// Returns `true` if a field that belongs to the views `only` belongs to `view`
+pub(crate) fn includes(only: &[Ident], view: &Ident) -> bool {
+    only.is_empty() || only.contains(view)
+}
+
+/// Builds the twin `ident` of a struct for `view`
+pub(crate) fn twin(
+    ident: &Ident,
+    vis: &Visibility,
+    generics: &Generics,
+    attrs: &[CompositeAttribute],
+    fields: &SpecialFields,
+    view: &Ident,
+) -> Special {
+    let SpecialFields::Named(named) = fields else {
+        unreachable!("views are only built for structs with named fields")
+    };
+
+    Special {
+        attrs: twin_attrs(attrs),
+        vis: vis.clone(),
+        ident: ident.clone(),
+        generics: generics.clone(),
+        body: Body::Struct(BodyStruct::named(
+            named
+                .named
+                .iter()
+                .filter(|field| includes(&only(&field.attrs), view))
+                .map(|field| twin_field(field, view))
+                .collect(),
+        )),
+    }
+}
+
+fn twin_field(field: &SpecialField, view: &Ident) -> SpecialField {
+    let (attrs, ty, fish) = match &field.ty {
+        SpecialType::Def(special) if has_twin(field, special) => {
+            let Body::Struct(BodyStruct { fields, .. }) = &special.body else {
+                unreachable!("checked by `has_twin`")
+            };
+            let twin = twin(
+                &twin_ident(&special.ident, &suffix(view)),
+                &special.vis,
+                &special.generics,
+                &special.attrs,
+                fields,
+                view,
+            );
+
+            (twin_field_attrs(&field.attrs, true), SpecialType::Def(twin), field.fish.clone())
+        }
+        // everything else is reused, the definitions in it are not defined again
+        ty => (
+            twin_field_attrs(&field.attrs, false),
+            SpecialType::Type(ty.reference(&field.fish)),
+            None,
+        ),
+    };
+
+    SpecialField {
+        attrs,
+        vis: field.vis.clone(),
+        mutability: FieldMutability::None,
+        ident: field.ident.clone(),
+        rename: field.rename.clone(),
+        question_token: field.question_token,
+        colon_token: field.colon_token,
+        ty,
+        fish,
+        default: field.default.clone(),
+        spread: field.spread,
+    }
+}
+
+/// Pairs of views `(from, into)` that can be converted into each other, because every field
+/// of `into` is also a field of `from`, in the struct and in all of its nested views
+pub(crate) fn conversions(fields: &SpecialFields, views: &[Ident]) -> Vec<(Ident, Ident)> {
+    let mut conversions = vec![];
+
+    for from in views {
+        for into in views {
+            if from != into && is_subset(fields, from, into) {
+                conversions.push((from.clone(), into.clone()));
+            }
+        }
+    }
+
+    conversions
+}
+
+fn is_subset(fields: &SpecialFields, from: &Ident, into: &Ident) -> bool {
+    let SpecialFields::Named(named) = fields else {
+        return true;
+    };
+
+    named.named.iter().all(|field| {
+        let only = only(&field.attrs);
+
+        if !includes(&only, into) {
+            return true;
+        }
+
+        match &field.ty {
+            SpecialType::Def(special) if has_twin(field, special) => {
+                let Body::Struct(BodyStruct { fields, .. }) = &special.body else {
+                    unreachable!("checked by `has_twin`")
+                };
+                includes(&only, from) && is_subset(fields, from, into)
+            }
+            _ => includes(&only, from),
+        }
+    })
+}
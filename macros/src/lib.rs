@@ -0,0 +1,157 @@
+//! The procedural macros of nestify, which expand with `nestify-core`.
+//!
+//! Use the `nestify` crate, which re-exports these macros.
+//!
+//! The macros that read JSON need the `json` feature.
+
+use proc_macro::TokenStream;
+use proc_macro_error::proc_macro_error;
+
+#[cfg(feature = "json")]
+use nestify_core as json;
+
+/// Stand-ins for the macros of the `json` feature, which report that it is disabled
+#[cfg(not(feature = "json"))]
+mod json {
+    use proc_macro2::TokenStream;
+    use proc_macro_error::abort_call_site;
+
+    fn disabled(name: &str) -> ! {
+        abort_call_site!(
+            "`{}!` requires the `json` feature of nestify", name;
+            help = "enable it with `nestify = { version = \"...\", features = [\"json\"] }`"
+        )
+    }
+
+    pub fn nest_from_json(_: TokenStream) -> TokenStream {
+        disabled("nest_from_json")
+    }
+
+    pub fn nest_from_json_file(_: TokenStream) -> TokenStream {
+        disabled("nest_from_json_file")
+    }
+
+    pub fn nest_from_schema(_: TokenStream) -> TokenStream {
+        disabled("nest_from_schema")
+    }
+
+    pub fn nest_openapi(_: TokenStream) -> TokenStream {
+        disabled("nest_openapi")
+    }
+}
+
+/// Allows for the expansion of "nested" items
+///
+/// # Learn
+/// See [Guide](https://github.com/snowfoxsh/nestify)
+///
+/// # Examples
+///
+/// Define a user profile with nested address and preferences structures
+/// ```
+/// nestify::nest! {
+///     struct UserProfile {
+///         name: String,
+///         address: struct Address {
+///             street: String,
+///             city: String,
+///         },
+///         preferences: struct Preferences {
+///             newsletter: bool,
+///         },
+///     }
+/// }
+/// ```
+///
+/// Define a task with a nested status enum
+/// ```
+/// nestify::nest! {
+///     struct Task {
+///         id: i32,
+///         description: String,
+///         status: enum Status {
+///             Pending,
+///             InProgress,
+///             Completed,
+///         },
+///     }
+/// }
+/// ```
+#[proc_macro]
+#[proc_macro_error]
+pub fn nest(input: TokenStream) -> TokenStream {
+    nestify_core::nest(input.into()).into()
+}
+
+/// Defines nested types from a sample of JSON
+///
+/// Objects become structs named after their key, arrays become `Vec`, and `null` or keys that
+/// are missing from some elements of an array become optional fields. Keys are renamed with
+/// serde, so the types are meant to derive `Deserialize`. Attributes and modifiers work like
+/// they do in [`nest!`].
+///
+/// # Examples
+///
+/// ```
+/// nestify::nest_from_json!(
+///     #[derive(Debug, serde::Deserialize)]*
+///     Response,
+///     r#"{ "user": { "id": 1, "displayName": "Ferris", "tags": ["crab"] } }"#
+/// );
+///
+/// let response: Response = serde_json::from_str(
+///     r#"{ "user": { "id": 2, "displayName": "Corro", "tags": [] } }"#
+/// ).unwrap();
+/// assert_eq!(response.user.display_name, "Corro");
+/// ```
+#[proc_macro]
+#[proc_macro_error]
+pub fn nest_from_json(input: TokenStream) -> TokenStream {
+    json::nest_from_json(input.into()).into()
+}
+
+/// Defines nested types from a file with a sample of JSON, see [`nest_from_json!`]
+///
+/// The path is relative to the directory of `Cargo.toml`.
+///
+/// ```ignore
+/// nestify::nest_from_json_file!(#[derive(serde::Deserialize)]* Response, "samples/response.json");
+/// ```
+#[proc_macro]
+#[proc_macro_error]
+pub fn nest_from_json_file(input: TokenStream) -> TokenStream {
+    json::nest_from_json_file(input.into()).into()
+}
+
+/// Defines nested types from a JSON Schema file
+///
+/// Objects become structs, `required` decides which fields are optional, enums of strings become
+/// enums with string values, and `oneOf` becomes an untagged enum with a variant for every
+/// alternative. `$ref` may point to definitions in the same file, such as `#/$defs/Address`.
+/// Attributes and modifiers work like they do in [`nest!`]. The path is relative to the
+/// directory of `Cargo.toml`.
+///
+/// ```ignore
+/// nestify::nest_from_schema!(#[derive(serde::Deserialize)]* User, "schemas/user.json");
+/// ```
+#[proc_macro]
+#[proc_macro_error]
+pub fn nest_from_schema(input: TokenStream) -> TokenStream {
+    json::nest_from_schema(input.into()).into()
+}
+
+/// Defines nested types for schemas of an OpenAPI document
+///
+/// Every schema listed in `components` is defined from `components/schemas`, like
+/// [`nest_from_schema!`] does, and anonymous objects in it are defined inline, named after their
+/// property. References between the listed schemas use their name. The document may be JSON or
+/// YAML, and the path is relative to the directory of `Cargo.toml`.
+///
+/// ```ignore
+/// nestify::nest_openapi!(#[derive(serde::Deserialize)]* pub "spec/openapi.yaml", components = [User, Order]);
+/// ```
+#[proc_macro]
+#[proc_macro_error]
+pub fn nest_openapi(input: TokenStream) -> TokenStream {
+    json::nest_openapi(input.into()).into()
+}
@@ -0,0 +1,79 @@
+//! The runtime description of the types defined with `#[nest::shape]`.
+//!
+//! Every type in the tree gets a `const SHAPE: Shape` with its fields and variants. Fields whose
+//! type is defined inline point to the shape of that definition, so the whole tree can be walked
+//! from the outermost type.
+
+/// The shape of a struct or enum
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shape {
+    /// Name of the type, without generics
+    pub name: &'static str,
+    pub body: Body,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Body {
+    Struct(Fields),
+    Enum(&'static [Variant]),
+}
+
+/// The fields of a struct or enum variant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fields {
+    Named(&'static [Field]),
+    Unnamed(&'static [Field]),
+    Unit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Field {
+    /// Name of the field, `None` for unnamed fields
+    pub name: Option<&'static str>,
+    /// The type of the field as it is emitted, such as `Option<Address>`
+    pub ty: &'static str,
+    /// The shape of the definition written inline as the type of the field,
+    /// either directly or as `Option<struct ...>`
+    pub inline: Option<&'static Shape>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Variant {
+    pub name: &'static str,
+    pub fields: Fields,
+}
+
+impl Shape {
+    /// The fields of a struct, empty for enums and unit structs
+    pub fn fields(&self) -> &'static [Field] {
+        match self.body {
+            Body::Struct(fields) => fields.as_slice(),
+            Body::Enum(_) => &[],
+        }
+    }
+
+    /// The variants of an enum, empty for structs
+    pub fn variants(&self) -> &'static [Variant] {
+        match self.body {
+            Body::Struct(_) => &[],
+            Body::Enum(variants) => variants,
+        }
+    }
+
+    /// The shapes of the definitions nested directly in this one, in the order they are written
+    pub fn children(&self) -> impl Iterator<Item = &'static Shape> {
+        let variants = self.variants().iter().flat_map(|variant| variant.fields.as_slice());
+
+        self.fields().iter().chain(variants).filter_map(|field| field.inline)
+    }
+}
+
+impl Fields {
+    /// Every field, empty for unit structs and variants
+    pub fn as_slice(&self) -> &'static [Field] {
+        match *self {
+            Fields::Named(fields) | Fields::Unnamed(fields) => fields,
+            Fields::Unit => &[],
+        }
+    }
+}
@@ -1 +0,0 @@
-mod test_attributes;
\ No newline at end of file
@@ -1 +0,0 @@
-mod attribute_modifier;
\ No newline at end of file
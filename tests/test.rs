@@ -2,7 +2,7 @@
 
 #![allow(dead_code, unused_variables)]
 
-use nestify::nest;
+use nestify::{nest, nest_from_json, nest_from_json_file, nest_from_schema};
 
 #[test]
 fn es() {}
@@ -465,4 +465,891 @@ fn partial_attribute_removal() {
         assert_impl_all!(Bar: Debug);
         assert_impl_none!(Bar: Clone);
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn dedup_identical_definitions() {
+    nest! {
+        #[nest::dedup]*
+        #[derive(Debug, Clone, PartialEq)]*
+        struct Order {
+            billing: struct Contact {
+                address: struct Address {
+                    city: String,
+                },
+            },
+            shipping: struct Destination {
+                address: struct Address {
+                    city: String,
+                },
+            },
+        }
+    }
+
+    let address = Address { city: "Berlin".to_string() };
+    let order = Order {
+        billing: Contact { address: address.clone() },
+        shipping: Destination { address },
+    };
+
+    assert_eq!(order.billing.address, order.shipping.address);
+}
+
+#[test]
+fn optional_field_shorthand() {
+    nest! {
+        #[derive(Debug, Default, PartialEq)]*
+        struct Profile {
+            name?: String,
+            address?: struct Address {
+                city?: String,
+            },
+            tags?: Vec<String>,
+        }
+    }
+
+    let profile = Profile {
+        name: Some("Ferris".to_string()),
+        address: Some(Address { city: None }),
+        tags: None,
+    };
+
+    assert_eq!(Profile::default().address, None);
+    assert_eq!(profile.address.unwrap().city, None);
+}
+
+#[test]
+fn optional_field_shorthand_serde() {
+    use serde::{Deserialize, Serialize};
+
+    nest! {
+        #[nest::serde]*
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]*
+        struct Response {
+            id: u64,
+            user?: struct User {
+                name: String,
+                email?: String,
+            },
+        }
+    }
+
+    let response: Response = serde_json::from_str(r#"{ "id": 1, "user": { "name": "Ferris" } }"#).unwrap();
+
+    assert_eq!(response.user, Some(User { name: "Ferris".to_string(), email: None }));
+    assert_eq!(serde_json::to_string(&response).unwrap(), r#"{"id":1,"user":{"name":"Ferris"}}"#);
+    assert_eq!(serde_json::to_string(&Response { id: 2, user: None }).unwrap(), r#"{"id":2}"#);
+}
+
+#[test]
+fn field_defaults() {
+    nest! {
+        #[derive(Debug, PartialEq)]*
+        struct Config {
+            retries: u32 = 3,
+            name: String = "client".to_string(),
+            verbose: bool,
+            mode: enum Mode {
+                Fast,
+                Slow,
+            } = Mode::Slow,
+            limits: struct Limits {
+                burst: u32 = 10,
+            } = Limits::default(),
+        }
+    }
+
+    let config = Config::default();
+
+    assert_eq!(config.retries, 3);
+    assert_eq!(config.name, "client");
+    assert!(!config.verbose);
+    assert_eq!(config.mode, Mode::Slow);
+    assert_eq!(config.limits.burst, 10);
+}
+
+#[test]
+fn field_defaults_serde() {
+    use serde::Deserialize;
+
+    nest! {
+        #[nest::serde]*
+        #[derive(Debug, Deserialize)]*
+        struct Config {
+            retries: u32 = 3,
+            timeout?: u64 = Some(30),
+            r#type: String = "basic".to_string(),
+        }
+    }
+
+    let config: Config = serde_json::from_str(r#"{ "retries": 5 }"#).unwrap();
+
+    assert_eq!(config.retries, 5);
+    assert_eq!(config.timeout, Some(30));
+    assert_eq!(config.r#type, "basic");
+    assert_eq!(Config::default().retries, 3);
+}
+
+#[test]
+fn field_defaults_serde_generic() {
+    use serde::Deserialize;
+
+    nest! {
+        #[nest::serde]*
+        #[derive(Debug, Deserialize)]*
+        struct Config<T> {
+            retries: u32 = 3,
+            inner: Vec<T>,
+        }
+    }
+
+    let config: Config<u8> = serde_json::from_str(r#"{ "inner": [1] }"#).unwrap();
+
+    assert_eq!(config.retries, 3);
+    assert_eq!(config.inner, [1]);
+}
+
+#[test]
+fn tuple_field_defaults() {
+    nest! {
+        struct Server(
+            String = "localhost".to_string(),
+            struct Port(u16 = 8080),
+        )
+    }
+
+    let server = Server::default();
+
+    assert_eq!(server.0, "localhost");
+    assert_eq!(server.1 .0, 8080);
+}
+
+#[test]
+fn string_literal_field_names() {
+    use serde::{Deserialize, Serialize};
+
+    nest! {
+        #[derive(Debug, Serialize, Deserialize)]*
+        struct Account {
+            "user-id": u64,
+            "@type": String,
+            "2fa_enabled": bool,
+            "displayName": String,
+            "profile": struct Profile {
+                "avatar-url"?: String,
+            },
+        }
+    }
+
+    let json = r#"{"user-id":7,"@type":"person","2fa_enabled":true,"displayName":"Ferris","profile":{"avatar-url":null}}"#;
+    let account: Account = serde_json::from_str(json).unwrap();
+
+    assert_eq!(account.user_id, 7);
+    assert_eq!(account.r#type, "person");
+    assert!(account._2fa_enabled);
+    assert_eq!(account.display_name, "Ferris");
+    assert_eq!(account.profile.avatar_url, None);
+    assert_eq!(serde_json::to_string(&account).unwrap(), json);
+}
+
+#[test]
+fn nested_builders() {
+    nest! {
+        #[nest::builder]*
+        #[derive(Debug, PartialEq)]*
+        struct Request {
+            id: u64,
+            note?: String,
+            user: struct User {
+                name: String,
+                retries: u32 = 3,
+                address?: struct Address {
+                    city: String,
+                    zip: Option<String>,
+                },
+            },
+            tags: Vec<String>,
+        }
+    }
+
+    let request = Request::builder()
+        .id(7)
+        .user(|user| user.name("Ferris".to_string()).address(|address| address.city("Berlin".to_string())))
+        .user(|user| user.address(|address| address.zip("10115".to_string())))
+        .tags(vec![])
+        .build()
+        .unwrap();
+
+    assert_eq!(request.note, None);
+    assert_eq!(request.user.retries, 3);
+    assert_eq!(request.user.name, "Ferris");
+    assert_eq!(
+        request.user.address,
+        Some(Address { city: "Berlin".to_string(), zip: Some("10115".to_string()) })
+    );
+
+    let missing = Request::builder().id(1).tags(vec![]).user(|user| user.address(|a| a)).build();
+    assert_eq!(missing, Err("missing field `name` in `User`"));
+}
+
+#[test]
+fn generated_constructors() {
+    nest! {
+        #[nest::new]*
+        #[derive(Debug, PartialEq)]*
+        struct User {
+            id: u64,
+            name: String,
+            role: enum Role {
+                Admin,
+                Member,
+            } = Role::Member,
+            address: struct Address(String, u16 = 80),
+            marker: #[derive(Default)] struct Marker,
+        }
+    }
+
+    let user = User::new(1, "Ferris", Address::new("Berlin"), Marker::new());
+
+    assert_eq!(user.name, "Ferris");
+    assert_eq!(user.role, Role::Member);
+    assert_eq!(user.address, Address("Berlin".to_string(), 80));
+}
+
+#[test]
+fn generated_accessors() {
+    nest! {
+        #[nest::accessors]*
+        #[derive(Default)]*
+        struct Profile {
+            name: String,
+            r#type: u8,
+            address?: struct Address {
+                city: String,
+            },
+            settings: Option<struct Settings {
+                theme: String,
+            }>,
+        }
+    }
+
+    let mut profile = Profile::default();
+
+    profile.set_name("Ferris".to_string()).set_type(2);
+    profile.address_or_insert_with(Address::default).set_city("Berlin".to_string());
+    profile.settings_or_insert_with(Settings::default).theme_mut().push_str("dark");
+
+    assert_eq!(profile.name(), "Ferris");
+    assert_eq!(*profile.r#type(), 2);
+    assert_eq!(profile.address().as_ref().unwrap().city(), "Berlin");
+    assert_eq!(profile.settings().as_ref().unwrap().theme(), "dark");
+}
+
+#[test]
+fn from_variant_payloads() {
+    nest! {
+        #[nest::from_variants]
+        #[derive(Debug, PartialEq)]*
+        enum Event {
+            Created(struct Created {
+                id: u64,
+            }),
+            Failed {
+                reason: enum Failure {
+                    Timeout,
+                    Rejected,
+                },
+            },
+            Deleted(u64),
+            Empty,
+        }
+    }
+
+    let event: Event = Created { id: 1 }.into();
+    assert_eq!(event, Event::Created(Created { id: 1 }));
+
+    let event = Event::from(Failure::Timeout);
+    assert_eq!(Failure::try_from(event), Ok(Failure::Timeout));
+
+    assert_eq!(Created::try_from(Event::Empty), Err(Event::Empty));
+}
+
+#[test]
+fn variant_inspection() {
+    nest! {
+        #[nest::inspect]*
+        #[derive(Debug, PartialEq)]*
+        enum Response {
+            Ok {
+                body: enum Body {
+                    Text(String),
+                    Pair(u8, u8),
+                    HttpError,
+                },
+            },
+            Redirect(String),
+            Empty,
+        }
+    }
+
+    let mut response = Response::Ok { body: Body::Pair(1, 2) };
+    assert!(response.is_ok());
+    assert!(!response.is_empty());
+    assert_eq!(response.as_ok().and_then(Body::as_pair), Some((&1, &2)));
+    assert!(Body::HttpError.is_http_error());
+
+    if let Some(Body::Pair(first, _)) = response.as_ok_mut() {
+        *first = 3;
+    }
+    assert_eq!(response.into_ok(), Ok(Body::Pair(3, 2)));
+
+    assert_eq!(Response::Redirect("/".to_string()).as_redirect().map(String::as_str), Some("/"));
+    assert_eq!(Response::Empty.into_redirect(), Err(Response::Empty));
+}
+
+#[test]
+fn string_enum_values() {
+    nest! {
+        #[nest::serde]*
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]*
+        struct Task {
+            status: enum Status {
+                Pending = "pending",
+                InProgress = "in_progress",
+                Done,
+            },
+        }
+    }
+
+    assert_eq!(Status::InProgress.to_string(), "in_progress");
+    assert_eq!(Status::Done.as_ref(), "Done");
+    assert_eq!("pending".parse(), Ok(Status::Pending));
+    assert!("unknown".parse::<Status>().is_err());
+    assert_eq!(Status::ALL, [Status::Pending, Status::InProgress, Status::Done]);
+
+    let task = Task { status: Status::InProgress };
+    let json = serde_json::to_string(&task).unwrap();
+    assert_eq!(json, r#"{"status":"in_progress"}"#);
+    assert_eq!(serde_json::from_str::<Task>(&json).unwrap(), task);
+}
+
+#[test]
+fn enum_discriminants() {
+    const OFFSET: u8 = 10;
+
+    nest! {
+        struct Packet {
+            #>[repr(u8)]
+            kind: enum Kind {
+                Ping = 1,
+                Pong,
+                Data(u8) = 0xff,
+            },
+            #>[repr(u8)]
+            level: enum Level {
+                Low = OFFSET,
+                High,
+            },
+        }
+    }
+
+    let _ = Kind::Data(0);
+    assert_eq!(Level::High as u8, 11);
+}
+
+#[test]
+fn spread_fields() {
+    nest! {
+        #[derive(Default)]*
+        struct Listing {
+            items: Vec<String>,
+            ..struct Pagination {
+                page: u32,
+                per_page: u32,
+            },
+        }
+    }
+
+    let listing = Listing::default();
+    let _: (&Vec<String>, u32, u32) = (&listing.items, listing.page, listing.per_page);
+}
+
+#[test]
+fn spread_fields_serde() {
+    nest! {
+        #[nest::serde]*
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]*
+        struct Listing {
+            items: Vec<String>,
+            ..struct Pagination {
+                page: u32,
+                per_page: u32,
+            },
+        }
+    }
+
+    let listing = Listing {
+        items: vec![],
+        pagination: Pagination { page: 2, per_page: 10 },
+    };
+    let json = serde_json::to_string(&listing).unwrap();
+    assert_eq!(json, r#"{"items":[],"page":2,"per_page":10}"#);
+    assert_eq!(serde_json::from_str::<Listing>(&json).unwrap(), listing);
+}
+
+#[test]
+fn struct_inheritance() {
+    nest! {
+        #[derive(Debug, PartialEq)]*
+        struct Api {
+            create: struct CreateUser {
+                name: String,
+                #>[derive(Default)]
+                role: enum Role {
+                    #[default]
+                    Member,
+                    Admin,
+                },
+                email?: String,
+            },
+            update: struct UpdateUser: CreateUser {
+                id: u64,
+                email: Option<String> = Some("unchanged".to_string()),
+            },
+            rename: struct RenameUser: UpdateUser {
+                new_name: String,
+            },
+        }
+    }
+
+    let update = UpdateUser {
+        name: "Ferris".to_string(),
+        role: Role::Admin,
+        id: 1,
+        email: None,
+    };
+    assert_eq!(update.role, Role::Admin);
+    assert_eq!(UpdateUser::default().email.as_deref(), Some("unchanged"));
+
+    let rename = RenameUser {
+        name: String::new(),
+        role: Role::Member,
+        id: 2,
+        email: None,
+        new_name: "Corro".to_string(),
+    };
+    assert_eq!(rename.id, 2);
+}
+
+#[test]
+fn partial_twins() {
+    nest! {
+        #[nest::partial(UserPatch)]
+        #[derive(Debug, Default, PartialEq)]*
+        struct User {
+            name: String,
+            address: struct Address {
+                city: String,
+                zip: String,
+            },
+            nickname?: String,
+            role: enum Role {
+                #[default]
+                Member,
+                Admin,
+            },
+        }
+    }
+
+    let mut user = User {
+        name: "Ferris".to_string(),
+        address: Address { city: "Berlin".to_string(), zip: "10115".to_string() },
+        nickname: None,
+        role: Role::Member,
+    };
+
+    user.apply(UserPatch {
+        address: Some(AddressPatch { city: Some("Paris".to_string()), zip: None }),
+        nickname: Some(Some("crab".to_string())),
+        role: Some(Role::Admin),
+        ..UserPatch::default()
+    });
+
+    assert_eq!(user.name, "Ferris");
+    assert_eq!(user.address, Address { city: "Paris".to_string(), zip: "10115".to_string() });
+    assert_eq!(user.nickname.as_deref(), Some("crab"));
+    assert_eq!(user.role, Role::Admin);
+}
+
+#[test]
+fn partial_twins_serde() {
+    nest! {
+        #[nest::partial(ListingPatch)]
+        #[nest::serde]*
+        #[derive(Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]*
+        struct Listing {
+            title: String,
+            ..struct Pagination {
+                page: u32,
+            },
+        }
+    }
+
+    let patch: ListingPatch = serde_json::from_str(r#"{"page":3}"#).unwrap();
+    assert_eq!(patch.title, None);
+    assert_eq!(serde_json::to_string(&patch).unwrap(), r#"{"page":3}"#);
+
+    let mut listing = Listing::default();
+    listing.apply(patch);
+    assert_eq!(listing.pagination.page, 3);
+}
+
+#[test]
+fn request_response_views() {
+    nest! {
+        #[nest::views(request, response)]
+        #[derive(Debug, Clone, PartialEq)]*
+        struct User {
+            name: String,
+            #[nest::only(request)]
+            password: String,
+            #[nest::only(response)]
+            id: u64,
+            profile: struct Profile {
+                bio: String,
+                #[nest::only(response)]
+                followers: u32,
+            },
+            #[nest::only(response)]
+            stats: struct Stats {
+                posts: u32,
+            },
+        }
+    }
+
+    let user = User {
+        name: "Ferris".to_string(),
+        password: "hunter2".to_string(),
+        id: 1,
+        profile: Profile { bio: "crab".to_string(), followers: 10 },
+        stats: Stats { posts: 3 },
+    };
+
+    let request = UserRequest::from(user.clone());
+    assert_eq!(request.password, "hunter2");
+    assert_eq!(request.profile, ProfileRequest { bio: "crab".to_string() });
+
+    let response: UserResponse = user.into();
+    assert_eq!(response.id, 1);
+    assert_eq!(response.profile.followers, 10);
+    assert_eq!(response.stats, StatsResponse { posts: 3 });
+}
+
+#[test]
+fn view_conversions() {
+    nest! {
+        #[nest::views(full, summary)]
+        #[derive(Debug, Clone, PartialEq)]*
+        struct Article {
+            title: String,
+            #[nest::only(full)]
+            body: String,
+            author: struct Author {
+                name: String,
+                #[nest::only(full)]
+                email: String,
+            },
+        }
+    }
+
+    let full = ArticleFull {
+        title: "Nesting".to_string(),
+        body: "...".to_string(),
+        author: AuthorFull { name: "Ferris".to_string(), email: "ferris@example.com".to_string() },
+    };
+
+    let summary = ArticleSummary::from(full);
+    assert_eq!(summary.author, AuthorSummary { name: "Ferris".to_string() });
+}
+
+#[test]
+fn versioned_types() {
+    nest! {
+        #[nest::versions(v1, v2, v3)]
+        #[derive(Debug, Clone, PartialEq)]*
+        struct User {
+            name: String,
+            #[nest::until(v3)]
+            nickname: String,
+            address: struct Address {
+                city: String,
+                #[nest::since(v2)]
+                zip?: String,
+            },
+            status: enum Status {
+                Active,
+                #[nest::since(v2)]
+                Suspended(String),
+            },
+        }
+    }
+
+    let user = v1::User {
+        name: "Ferris".to_string(),
+        nickname: "crab".to_string(),
+        address: v1::Address { city: "Berlin".to_string() },
+        status: v1::Status::Active,
+    };
+
+    let user = v2::User::from(user);
+    assert_eq!(user.address, v2::Address { city: "Berlin".to_string(), zip: None });
+    assert_eq!(user.status, v2::Status::Active);
+
+    let user = v3::User {
+        name: user.name,
+        address: v3::Address { city: "Paris".to_string(), zip: Some("75001".to_string()) },
+        status: v3::Status::Suspended("spam".to_string()),
+    };
+    assert_eq!(user.address.zip.as_deref(), Some("75001"));
+}
+
+#[test]
+fn version_upgrades() {
+    nest! {
+        #[nest::versions(v1, v2)]
+        #[derive(Debug, PartialEq)]
+        struct Settings {
+            theme: String,
+            #[nest::since(v2)]
+            font_size: u32 = 14,
+            #[nest::since(v2)]
+            language?: String,
+        }
+    }
+
+    let settings: v2::Settings = v1::Settings { theme: "dark".to_string() }.into();
+    assert_eq!(
+        settings,
+        v2::Settings { theme: "dark".to_string(), font_size: 14, language: None }
+    );
+}
+
+#[test]
+fn version_upgrades_of_collections() {
+    nest! {
+        #[nest::versions(v1, v2)]
+        #[derive(Debug, PartialEq)]*
+        struct Cart {
+            items: Vec<struct Item {
+                name: String,
+                #[nest::since(v2)]
+                quantity: u32 = 1,
+            }>,
+            gift?: Box<struct Gift {
+                message: String,
+            }>,
+            totals: std::collections::HashMap<String, struct Total {
+                amount: u64,
+            }>,
+        }
+    }
+
+    let cart: v2::Cart = v1::Cart {
+        items: vec![v1::Item { name: "book".to_string() }],
+        gift: Some(Box::new(v1::Gift { message: "enjoy".to_string() })),
+        totals: std::collections::HashMap::from([("EUR".to_string(), v1::Total { amount: 12 })]),
+    }
+    .into();
+    assert_eq!(cart.items, [v2::Item { name: "book".to_string(), quantity: 1 }]);
+    assert_eq!(cart.gift.unwrap().message, "enjoy");
+    assert_eq!(cart.totals["EUR"], v2::Total { amount: 12 });
+}
+
+#[test]
+fn shape_reflection() {
+    use nestify::shape::{Body, Fields};
+
+    nest! {
+        #[nest::shape]
+        struct User {
+            name: String,
+            address?: struct Address {
+                city: String,
+            },
+            status: enum Status {
+                Active,
+                Moved(struct Move {
+                    to: String,
+                }),
+            },
+        }
+    }
+
+    let shape = User::SHAPE;
+    assert_eq!(shape.name, "User");
+
+    let names: Vec<_> = shape.fields().iter().map(|field| field.name.unwrap()).collect();
+    assert_eq!(names, ["name", "address", "status"]);
+
+    let address = &shape.fields()[1];
+    assert_eq!(address.ty, "Option<Address>");
+    assert_eq!(address.inline, Some(&Address::SHAPE));
+    assert_eq!(shape.fields()[0].inline, None);
+
+    let children: Vec<_> = shape.children().map(|child| child.name).collect();
+    assert_eq!(children, ["Address", "Status"]);
+
+    let Body::Enum(variants) = Status::SHAPE.body else {
+        panic!("expected an enum");
+    };
+    assert_eq!(variants[0].fields, Fields::Unit);
+    assert_eq!(variants[1].name, "Moved");
+    assert_eq!(variants[1].fields.as_slice()[0].inline, Some(&Move::SHAPE));
+}
+
+#[test]
+fn expansion_dump() {
+    nest! {
+        #[nest::dump]
+        #[allow(dead_code)]*
+        struct User {
+            name: String,
+            address: #[allow(dead_code)]- #[derive(Default)] struct Address {
+                city: String,
+            },
+        }
+    }
+
+    assert_eq!(
+        User::NESTIFY_EXPANSION,
+        "#[allow(dead_code)]
+struct User {
+    name: String,
+    address: Address,
+}
+#[derive(Default)]
+struct Address {
+    city: String,
+}
+"
+    );
+}
+
+#[test]
+fn expansion_dumps_in_one_module() {
+    nest! {
+        #[nest::dump]
+        #[allow(dead_code)]
+        struct Wrapper<T> {
+            value: T,
+        }
+    }
+    nest! {
+        #[nest::dump]
+        #[nest::versions(v1, v2)]
+        #[allow(dead_code)]*
+        enum Status {
+            Active,
+            #[nest::since(v2)]
+            Banned,
+        }
+    }
+
+    assert!(Wrapper::<u8>::NESTIFY_EXPANSION.starts_with("#[allow(dead_code)]\nstruct Wrapper<T> {"));
+    assert_eq!(v1::Status::NESTIFY_EXPANSION, v2::Status::NESTIFY_EXPANSION);
+    assert!(v2::Status::NESTIFY_EXPANSION.contains("mod v2 {"));
+}
+
+#[test]
+fn types_from_json() {
+    nest_from_json!(
+        #[derive(Debug, serde::Deserialize)]*
+        Response,
+        r#"{
+            "user": { "id": 1, "displayName": "Ferris", "tags": ["crab"] },
+            "lastSeen": null
+        }"#
+    );
+
+    let response: Response = serde_json::from_str(
+        r#"{ "user": { "id": 2, "displayName": "Corro", "tags": [] } }"#,
+    )
+    .unwrap();
+
+    assert_eq!(response.user.id, 2);
+    assert_eq!(response.user.display_name, "Corro");
+    assert!(response.user.tags.is_empty());
+    assert_eq!(response.last_seen, None);
+}
+
+#[test]
+fn types_from_json_file() {
+    nest_from_json_file!(
+        #[derive(Debug, serde::Deserialize)]*
+        Order,
+        "tests/samples/order.json"
+    );
+
+    let order: Order = serde_json::from_str(include_str!("samples/order.json")).unwrap();
+
+    assert_eq!(order.order_id, 1042);
+    assert_eq!(order.customer.email, None);
+    assert_eq!(order.items[1].price, 12.0);
+    assert_eq!(order.items[0].gift_wrap, None);
+    assert_eq!(order.items[1].gift_wrap, Some(true));
+}
+
+#[test]
+fn types_from_schema() {
+    nest_from_schema!(
+        #[derive(Debug, PartialEq, serde::Deserialize)]*
+        User,
+        "tests/samples/user.schema.json"
+    );
+
+    let user: User = serde_json::from_str(
+        r#"{
+            "id": 7,
+            "name": "Ferris",
+            "status": "on-hold",
+            "home": { "city": "Berlin" },
+            "work": { "city": "Paris", "zip": "75001" },
+            "contact": { "number": "555-0100" }
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(user.nick_name, None);
+    assert_eq!(user.status, Status::OnHold);
+    assert_eq!(user.home, Address { city: "Berlin".to_string(), zip: None });
+    assert_eq!(user.work.unwrap().zip.as_deref(), Some("75001"));
+    assert_eq!(user.tags, None);
+    assert_eq!(user.contact, Some(Contact::Phone(Phone { number: "555-0100".to_string() })));
+}
+
+#[test]
+fn types_from_openapi() {
+    mod api {
+        nestify::nest_openapi!(
+            #[derive(Debug, PartialEq, serde::Deserialize)]*
+            pub "tests/samples/openapi.yaml",
+            components = [User, Order]
+        );
+    }
+
+    let order: api::Order = serde_json::from_str(
+        r#"{
+            "id": 1,
+            "buyer": { "id": 2, "name": "Ferris", "preferences": { "newsletter": true } },
+            "shipping": { "city": "Berlin" },
+            "lines": [{ "sku": "CRAB-1", "quantity": 3 }]
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(order.buyer.name, "Ferris");
+    assert_eq!(order.buyer.preferences, Some(api::Preferences { newsletter: Some(true) }));
+    assert_eq!(order.shipping, Some(api::Address { city: "Berlin".to_string() }));
+    assert_eq!(order.lines[0], api::LinesItem { sku: "CRAB-1".to_string(), quantity: 3 });
+    assert_eq!(order.note, None);
+}